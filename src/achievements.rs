@@ -0,0 +1,107 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration
+};
+
+use serde::{Deserialize, Serialize};
+use sudoku_core::Difficulty;
+
+use crate::stats::{GameStats, Records};
+
+/// How long a win can take and still count as quick.
+const QUICK_WIN: Duration = Duration::from_secs(5 * 60);
+/// How many daily puzzles in a row make a week's streak.
+const WEEK: u32 = 7;
+
+/// Something done once in a game, remembered from then on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Achievement {
+    /// Won any game.
+    FirstWin,
+    /// Won a rated game without a mistake, hint or reveal.
+    Flawless,
+    /// Won in under five minutes.
+    QuickWin,
+    /// Won an expert puzzle without a hint or reveal.
+    ExpertUnaided,
+    /// Watched the solver fill the board step by step from the givens.
+    WatchedSolve,
+    /// Won the daily puzzle a week of days in a row.
+    WeekStreak
+}
+
+impl Achievement {
+    /// Every achievement, in the order they are listed.
+    pub const ALL: [Achievement; 6] = [
+        Achievement::FirstWin,
+        Achievement::Flawless,
+        Achievement::QuickWin,
+        Achievement::ExpertUnaided,
+        Achievement::WatchedSolve,
+        Achievement::WeekStreak
+    ];
+
+    /// The name shown when it is unlocked.
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::FirstWin => "First win",
+            Achievement::Flawless => "Flawless",
+            Achievement::QuickWin => "Quick win",
+            Achievement::ExpertUnaided => "Pure expert",
+            Achievement::WatchedSolve => "Spectator",
+            Achievement::WeekStreak => "Week streak"
+        }
+    }
+
+    /// Whether a game that just ended after `time` earns it, with the
+    /// lifetime stats already counting the game.
+    fn earned_by(self, game: &GameStats, won: bool, time: Duration, records: &Records) -> bool {
+        let unaided = game.hints == 0;
+        match self {
+            Achievement::FirstWin => won,
+            Achievement::Flawless => won && unaided && game.mistakes == 0 && game.difficulty.is_some(),
+            Achievement::QuickWin => won && time < QUICK_WIN,
+            Achievement::ExpertUnaided => won && unaided && game.difficulty == Some(Difficulty::Expert),
+            Achievement::WatchedSolve => game.solver.is_some() && game.watched,
+            Achievement::WeekStreak => records.streak >= WEEK
+        }
+    }
+}
+
+/// The file the achievements are kept in, in the platform's data directory
+/// for the app. Returns `None` if SDL can't find or create it.
+pub fn achievements_path() -> Option<PathBuf> {
+    let dir = sdl2::filesystem::pref_path("GardSvard", "sudoku-solver").ok()?;
+    Some(Path::new(&dir).join("achievements.json"))
+}
+
+/// The achievements unlocked so far, kept between runs.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Achievements {
+    /// In the order they were unlocked.
+    pub unlocked: Vec<Achievement>
+}
+
+impl Achievements {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Unlocks whatever a game that just ended earns, returning the newly
+    /// unlocked achievements.
+    pub fn unlock(&mut self, game: &GameStats, won: bool, time: Duration, records: &Records) -> Vec<Achievement> {
+        let new: Vec<Achievement> = Achievement::ALL.into_iter()
+            .filter(|achievement| !self.unlocked.contains(achievement) && achievement.earned_by(game, won, time, records))
+            .collect();
+        self.unlocked.extend(&new);
+        new
+    }
+}
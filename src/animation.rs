@@ -0,0 +1,62 @@
+use std::time::{Duration, Instant};
+
+use sudoku_core::{render::Fade, Board, Tile};
+
+/// How long a digit takes to fade in or out, unless the solver is faster.
+pub const FADE_TIME: Duration = Duration::from_millis(200);
+
+/// Digits fading in as the solver places them and out as it takes them back.
+#[derive(Default)]
+pub struct Animations {
+    fades: Vec<Fading>
+}
+
+struct Fading {
+    pos: (i8, i8),
+    digit: u8,
+    appearing: bool,
+    started: Instant,
+    duration: Duration
+}
+
+impl Animations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts fading in each digit that is on `after` but not `before`, and
+    /// fading out each one that was taken away, over `duration`.
+    pub fn changed(&mut self, before: &Board, after: &Board, duration: Duration) {
+        for y in 0..9 {
+            for x in 0..9 {
+                let (old, new) = (before.get((x, y)), after.get((x, y)));
+                if old == new {
+                    continue;
+                }
+                let pos = (x as i8, y as i8);
+                self.fades.retain(|fading| fading.pos != pos);
+                let started = Instant::now();
+                if let Tile::Soft(digit) = old {
+                    self.fades.push(Fading { pos, digit, appearing: false, started, duration });
+                }
+                if let Tile::Soft(digit) = new {
+                    self.fades.push(Fading { pos, digit, appearing: true, started, duration });
+                }
+            }
+        }
+    }
+
+    /// The fades still underway, with how visible each digit is by now.
+    /// Finished ones are forgotten.
+    pub fn current(&mut self) -> Vec<Fade> {
+        self.fades.retain(|fading| fading.started.elapsed() < fading.duration);
+        self.fades.iter().map(|fading| {
+            let done = fading.started.elapsed().as_secs_f32() / fading.duration.as_secs_f32();
+            Fade { pos: fading.pos, digit: fading.digit, opacity: if fading.appearing { done } else { 1.0 - done } }
+        }).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.fades.clear();
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    io::{self, Read},
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, TrySendError},
+    thread
+};
+
+use crate::ocr::GrayImage;
+
+/// The size frames are scaled to, whatever the camera delivers.
+pub const FRAME_WIDTH: usize = 640;
+pub const FRAME_HEIGHT: usize = 480;
+
+/// The camera the config file starts out with on this platform, in the form
+/// ffmpeg takes for its input device.
+pub fn default_device() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "0"
+    } else if cfg!(windows) {
+        "video=Integrated Camera"
+    } else {
+        "/dev/video0"
+    }
+}
+
+/// A webcam streaming grayscale frames through `ffmpeg`, which has to be
+/// installed, read on a worker thread so the window stays responsive.
+pub struct Camera {
+    ffmpeg: Child,
+    receiver: Receiver<GrayImage>,
+    frame: Option<GrayImage>
+}
+
+impl Camera {
+    pub fn open(device: &str) -> io::Result<Self> {
+        let format = if cfg!(target_os = "macos") {
+            "avfoundation"
+        } else if cfg!(windows) {
+            "dshow"
+        } else {
+            "v4l2"
+        };
+        let mut ffmpeg = Command::new("ffmpeg")
+            .args(["-loglevel", "quiet", "-f", format, "-i", device])
+            .args(["-vf", &format!("scale={FRAME_WIDTH}:{FRAME_HEIGHT}"), "-f", "rawvideo", "-pix_fmt", "gray", "-"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let mut stdout = ffmpeg.stdout.take().expect("stdout is piped");
+        // Room for one frame, so the window always gets the newest one
+        let (sender, receiver) = mpsc::sync_channel(1);
+        thread::spawn(move || {
+            loop {
+                let mut pixels = vec![0; FRAME_WIDTH * FRAME_HEIGHT];
+                if stdout.read_exact(&mut pixels).is_err() {
+                    break;
+                }
+                match sender.try_send(GrayImage { width: FRAME_WIDTH, height: FRAME_HEIGHT, pixels }) {
+                    Ok(()) | Err(TrySendError::Full(_)) => (),
+                    Err(TrySendError::Disconnected(_)) => break
+                }
+            }
+        });
+
+        Ok(Self { ffmpeg, receiver, frame: None })
+    }
+
+    /// The newest frame so far, or `None` if the camera hasn't sent one yet.
+    pub fn poll(&mut self) -> Option<&GrayImage> {
+        if let Some(frame) = self.receiver.try_iter().last() {
+            self.frame = Some(frame);
+        }
+        self.frame.as_ref()
+    }
+
+    /// Returns false once ffmpeg has stopped, as when there is no such camera.
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.ffmpeg.try_wait(), Ok(None))
+    }
+}
+
+impl Drop for Camera {
+    fn drop(&mut self) {
+        let _ = self.ffmpeg.kill();
+        let _ = self.ffmpeg.wait();
+    }
+}
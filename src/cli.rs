@@ -0,0 +1,206 @@
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{self, BufRead, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant}
+};
+
+use sudoku_core::{
+    default_threads, find_solution, generate_many, rate, solve_with_budget, to_pdf, Board, Budget, GenerationStrategy, Outcome,
+    PdfOptions, Rng, SolverOptions, Strategy
+};
+
+use crate::files::{self, Loaded};
+
+/// `generate [--count N] [--out FILE] [--threads N] [--seed N] [--strategy dig|add]`
+///
+/// Writes `count` puzzles, one per line as 81 characters followed by their
+/// difficulty, to `FILE` or to stdout. A summary of the givens and work the
+/// strategy needed goes to stderr.
+pub fn generate(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut count = 1;
+    let mut out = None;
+    let mut threads = default_threads();
+    let mut rng = Rng::from_entropy();
+    let mut strategy = GenerationStrategy::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--count" => count = value()?.parse()?,
+            "--out" => out = Some(value()?.clone()),
+            "--threads" => threads = value()?.parse()?,
+            "--seed" => rng = Rng::new(value()?.parse()?),
+            "--strategy" => {
+                let name = value()?;
+                strategy = GenerationStrategy::from_name(name).ok_or_else(|| format!("unknown strategy {name}"))?;
+            },
+            _ => return Err(format!("unknown argument {arg}").into())
+        }
+    }
+
+    let start = Instant::now();
+    let puzzles = generate_many(count, threads, strategy, &mut rng);
+
+    let mut writer: BufWriter<Box<dyn Write>> = BufWriter::new(match &out {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout())
+    });
+    for generated in &puzzles {
+        let difficulty = rate(&generated.puzzle).expect("generated puzzles have a unique solution");
+        writeln!(writer, "{} {difficulty}", generated.puzzle.to_line())?;
+    }
+    writer.flush()?;
+
+    let total = puzzles.len().max(1) as f64;
+    let clues = puzzles.iter().map(|generated| generated.clues).sum::<usize>() as f64 / total;
+    let checks = puzzles.iter().map(|generated| generated.checks).sum::<usize>() as f64 / total;
+    eprintln!(
+        "Generated {count} puzzles in {:.2?} with the {} strategy, averaging {clues:.1} givens and {checks:.1} uniqueness checks",
+        start.elapsed(),
+        strategy.name()
+    );
+    Ok(())
+}
+
+/// `print FILE --out OUT.pdf [--per-page N] [--solutions]`
+///
+/// Lays out the puzzles in `FILE`, read as for `--file`, as a PDF for
+/// printing, optionally followed by pages with their solutions.
+pub fn print(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut input = None;
+    let mut out = None;
+    let mut options = PdfOptions::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--out" => out = Some(value()?.clone()),
+            "--per-page" => options.per_page = value()?.parse()?,
+            "--solutions" => options.solutions = true,
+            _ if input.is_none() && !arg.starts_with("--") => input = Some(arg.clone()),
+            _ => return Err(format!("unknown argument {arg}").into())
+        }
+    }
+    let input = input.ok_or("print needs a file with puzzles")?;
+    let out = out.ok_or("print needs --out")?;
+
+    let puzzles = match files::load(Path::new(&input))? {
+        Loaded::Puzzle(puzzle) => vec![puzzle.board],
+        Loaded::Collection(puzzles) => puzzles.into_iter().map(|(board, _)| board).collect()
+    };
+    fs::write(&out, to_pdf(&puzzles, options))?;
+    eprintln!("Wrote {} puzzles to {out}", puzzles.len());
+    Ok(())
+}
+
+/// `solve`
+///
+/// Reads one puzzle per line from stdin and writes its solution to stdout as
+/// soon as it is found, so it can sit in the middle of a pipeline. As in a
+/// `.sdm` file, only the first word of each line is read, and blank lines and
+/// `#` comments are skipped. Puzzles without a solution are written as
+/// `unsolvable` and lines that aren't a puzzle as `invalid`, with the reason
+/// on stderr, so the output keeps one line per puzzle.
+pub fn solve(args: &[String]) -> Result<(), Box<dyn Error>> {
+    if let Some(arg) = args.first() {
+        return Err(format!("unknown argument {arg}").into());
+    }
+
+    let mut stdout = io::stdout().lock();
+    for (idx, line) in io::stdin().lock().lines().enumerate() {
+        let line = line?;
+        let Some(puzzle) = line.split_whitespace().next().filter(|puzzle| !puzzle.starts_with('#')) else {
+            continue;
+        };
+        match puzzle.parse::<Board>() {
+            Ok(board) if board.is_valid() => match find_solution(&board) {
+                Some(solution) => writeln!(stdout, "{}", solution.to_line())?,
+                None => writeln!(stdout, "unsolvable")?
+            },
+            Ok(_) => {
+                eprintln!("line {}: the puzzle breaks the rules", idx + 1);
+                writeln!(stdout, "invalid")?;
+            },
+            Err(err) => {
+                eprintln!("line {}: {err}", idx + 1);
+                writeln!(stdout, "invalid")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `bench --file FILE [--strategy brute-force|logic-first|dlx]`
+///
+/// Solves every puzzle in `FILE`, read as for `--file`, and prints the time,
+/// nodes and backtracks each one took, followed by the minimum, median,
+/// maximum and total of each.
+pub fn bench(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut input = None;
+    let mut options = SolverOptions::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--file" => input = Some(value()?.clone()),
+            "--strategy" => {
+                options.strategy = match value()?.as_str() {
+                    "brute-force" => Strategy::BruteForce,
+                    "logic-first" => Strategy::LogicFirst,
+                    "dlx" => Strategy::Dlx,
+                    name => return Err(format!("unknown strategy {name}").into())
+                };
+            },
+            _ => return Err(format!("unknown argument {arg}").into())
+        }
+    }
+    let input = input.ok_or("bench needs --file")?;
+
+    let puzzles = match files::load(Path::new(&input))? {
+        Loaded::Puzzle(puzzle) => vec![puzzle.board],
+        Loaded::Collection(puzzles) => puzzles.into_iter().map(|(board, _)| board).collect()
+    };
+
+    let mut times = Vec::new();
+    let mut nodes = Vec::new();
+    let mut backtracks = Vec::new();
+    println!("{:>6} {:>12} {:>10} {:>10}", "puzzle", "time", "nodes", "backtracks");
+    for (idx, puzzle) in puzzles.iter().enumerate() {
+        let mut board = *puzzle;
+        let mut solver = options.build();
+        let start = Instant::now();
+        let (outcome, progress) = solve_with_budget(solver.as_mut(), &mut board, Budget::default(), |_| ());
+        let time = start.elapsed();
+
+        let note = if outcome == Outcome::Solved { "" } else { " (unsolvable)" };
+        println!("{:>6} {:>12.2?} {:>10} {:>10}{note}", idx + 1, time, progress.nodes, progress.backtracks);
+        times.push(time);
+        nodes.push(progress.nodes);
+        backtracks.push(progress.backtracks);
+    }
+    if puzzles.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    println!("Solved {} puzzles with the {} strategy", puzzles.len(), options.strategy);
+    println!("{:>10} {:>12} {:>12} {:>12} {:>12}", "", "min", "median", "max", "total");
+    let [min, median, max] = summary(&mut times);
+    println!("{:>10} {min:>12.2?} {median:>12.2?} {max:>12.2?} {:>12.2?}", "time", times.iter().sum::<Duration>());
+    for (name, values) in [("nodes", &mut nodes), ("backtracks", &mut backtracks)] {
+        let [min, median, max] = summary(values);
+        println!("{name:>10} {min:>12} {median:>12} {max:>12} {:>12}", values.iter().sum::<u64>());
+    }
+    Ok(())
+}
+
+/// The minimum, median and maximum of `values`, which mustn't be empty.
+fn summary<T: Copy + Ord>(values: &mut [T]) -> [T; 3] {
+    values.sort_unstable();
+    [values[0], values[values.len() / 2], values[values.len() - 1]]
+}
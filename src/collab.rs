@@ -0,0 +1,207 @@
+use std::{
+    error::Error,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex
+    },
+    thread,
+    time::Duration
+};
+
+use serde::{Deserialize, Serialize};
+use sudoku_core::Board;
+use tungstenite::{stream::MaybeTlsStream, WebSocket};
+
+use crate::Action;
+
+/// The host is always player 0, and those who join are numbered from 1.
+pub const HOST: u8 = 0;
+
+/// How long a connection waits for a message before checking for ones to send.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// What the players of a shared board tell each other, sent as JSON.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Message {
+    /// Sent by the host to a player who just joined, with their number.
+    Welcome { player: u8 },
+    Joined { player: u8 },
+    Left { player: u8 },
+    Cursor { player: u8, cursor: (i8, i8) },
+    /// A digit written or removed by `player`, at their cursor, as a given
+    /// if they were editing the puzzle.
+    Action { player: u8, cursor: (i8, i8), action: Action, given: bool },
+    /// The whole board, when it is replaced rather than edited, as by loading
+    /// a puzzle, and for players who just joined.
+    Board { player: u8, board: Box<Board> }
+}
+
+impl Message {
+    /// The message as sent by `player`, whatever number it claims to be
+    /// from, or `None` if only the host may send it.
+    fn sent_by(self, player: u8) -> Option<Self> {
+        match self {
+            Message::Welcome { .. } | Message::Joined { .. } | Message::Left { .. } => None,
+            Message::Cursor { cursor, .. } => Some(Message::Cursor { player, cursor }),
+            Message::Action { cursor, action, given, .. } => Some(Message::Action { player, cursor, action, given }),
+            Message::Board { board, .. } => Some(Message::Board { player, board })
+        }
+    }
+}
+
+type Clients = Arc<Mutex<Vec<(u8, Sender<Message>)>>>;
+
+enum Peers {
+    /// The outboxes of everyone who joined.
+    Host(Clients),
+    /// The outbox of the connection to the host.
+    Client(Sender<Message>)
+}
+
+/// A board shared with other players over WebSocket.
+///
+/// The host passes every message from one player on to the others, so
+/// everyone sees every edit and cursor. Connections are served on worker
+/// threads so the window stays responsive.
+pub struct Session {
+    pub player: u8,
+    incoming: Receiver<Message>,
+    peers: Peers
+}
+
+impl Session {
+    /// Waits for players to join on `port`.
+    pub fn host(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let clients: Clients = Arc::default();
+        let (to_window, incoming) = mpsc::channel();
+
+        let accepting = Arc::clone(&clients);
+        thread::spawn(move || {
+            let mut next_player = HOST + 1;
+            for stream in listener.incoming().flatten() {
+                let player = next_player;
+                next_player = next_player.wrapping_add(1).max(HOST + 1);
+                let (clients, to_window) = (Arc::clone(&accepting), to_window.clone());
+                thread::spawn(move || serve_client(stream, player, &clients, &to_window));
+            }
+        });
+
+        Ok(Self { player: HOST, incoming, peers: Peers::Host(clients) })
+    }
+
+    /// Joins the board hosted at `address`, such as `192.168.1.10:9000` or
+    /// `ws://192.168.1.10:9000`.
+    pub fn join(address: &str) -> Result<Self, Box<dyn Error>> {
+        let url = if address.starts_with("ws://") { address.to_string() } else { format!("ws://{address}") };
+        let (mut socket, _) = tungstenite::connect(&url)?;
+        let player = loop {
+            match receive(&mut socket)? {
+                Some(Message::Welcome { player }) => break player,
+                Some(_) => return Err("the host didn't say who we are".into()),
+                None => ()
+            }
+        };
+        if let MaybeTlsStream::Plain(stream) = socket.get_mut() {
+            stream.set_read_timeout(Some(POLL_INTERVAL))?;
+        }
+
+        let (to_window, incoming) = mpsc::channel();
+        let (to_host, outbox) = mpsc::channel();
+        thread::spawn(move || {
+            pump(&mut socket, &outbox, |message| {
+                let _ = to_window.send(message);
+            });
+            let _ = to_window.send(Message::Left { player: HOST });
+        });
+
+        Ok(Self { player, incoming, peers: Peers::Client(to_host) })
+    }
+
+    pub fn is_host(&self) -> bool {
+        matches!(self.peers, Peers::Host(_))
+    }
+
+    /// Tells every other player.
+    pub fn send(&self, message: Message) {
+        match &self.peers {
+            Peers::Host(clients) => relay(clients, HOST, &message),
+            Peers::Client(to_host) => {
+                let _ = to_host.send(message);
+            }
+        }
+    }
+
+    /// The messages from other players since the last call.
+    pub fn poll(&self) -> Vec<Message> {
+        self.incoming.try_iter().collect()
+    }
+}
+
+/// Passes a message from `from` on to everyone else who joined.
+fn relay(clients: &Clients, from: u8, message: &Message) {
+    for (player, outbox) in clients.lock().expect("no thread panics while holding the lock").iter() {
+        if *player != from {
+            let _ = outbox.send(message.clone());
+        }
+    }
+}
+
+/// Talks to one player who joined, until they leave.
+fn serve_client(stream: TcpStream, player: u8, clients: &Clients, to_window: &Sender<Message>) {
+    let Ok(mut socket) = tungstenite::accept(stream) else {
+        return;
+    };
+    let _ = socket.get_mut().set_read_timeout(Some(POLL_INTERVAL));
+
+    let (outbox, outgoing) = mpsc::channel();
+    let _ = outbox.send(Message::Welcome { player });
+    clients.lock().expect("no thread panics while holding the lock").push((player, outbox));
+    relay(clients, player, &Message::Joined { player });
+    let _ = to_window.send(Message::Joined { player });
+
+    pump(&mut socket, &outgoing, |message| {
+        let Some(message) = message.sent_by(player) else {
+            return;
+        };
+        relay(clients, player, &message);
+        let _ = to_window.send(message);
+    });
+
+    clients.lock().expect("no thread panics while holding the lock").retain(|&(other, _)| other != player);
+    relay(clients, player, &Message::Left { player });
+    let _ = to_window.send(Message::Left { player });
+}
+
+/// Sends what is waiting in `outgoing` and hands what arrives to
+/// `on_message`, until the connection closes.
+fn pump<S: Read + Write>(socket: &mut WebSocket<S>, outgoing: &Receiver<Message>, mut on_message: impl FnMut(Message)) {
+    loop {
+        for message in outgoing.try_iter() {
+            let Ok(text) = serde_json::to_string(&message) else {
+                continue;
+            };
+            if socket.send(tungstenite::Message::text(text)).is_err() {
+                return;
+            }
+        }
+        match receive(socket) {
+            Ok(Some(message)) => on_message(message),
+            Ok(None) => (),
+            Err(_) => return
+        }
+    }
+}
+
+/// The next message, or `None` if nothing arrived in time or it wasn't one
+/// of ours.
+fn receive<S: Read + Write>(socket: &mut WebSocket<S>) -> Result<Option<Message>, Box<tungstenite::Error>> {
+    match socket.read() {
+        Ok(tungstenite::Message::Text(text)) => Ok(serde_json::from_str(&text).ok()),
+        Ok(_) => Ok(None),
+        Err(tungstenite::Error::Io(err)) if matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => Ok(None),
+        Err(err) => Err(Box::new(err))
+    }
+}
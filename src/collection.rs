@@ -0,0 +1,78 @@
+use std::cell::OnceCell;
+
+use sudoku_core::{rate, Board, Difficulty};
+
+/// Puzzles loaded together from one file, such as a downloaded puzzle pack.
+pub struct Collection {
+    /// Every puzzle with its label, if the file gave it one.
+    puzzles: Vec<(Board, Option<String>)>,
+    index: usize,
+    /// Each puzzle's difficulty, rated the first time it is asked for.
+    ratings: Vec<OnceCell<Option<Difficulty>>>,
+    /// Which puzzles the player has solved since the collection was loaded.
+    solved: Vec<bool>
+}
+
+impl Collection {
+    /// Starts at the first puzzle. Returns `None` if there are no puzzles.
+    pub fn new(puzzles: Vec<(Board, Option<String>)>) -> Option<Self> {
+        let (ratings, solved) = (vec![OnceCell::new(); puzzles.len()], vec![false; puzzles.len()]);
+        (!puzzles.is_empty()).then_some(Self { puzzles, index: 0, ratings, solved })
+    }
+
+    pub fn current(&self) -> Board {
+        self.puzzles[self.index].0
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.puzzles[self.index].1.as_deref()
+    }
+
+    /// Moves to the next puzzle, wrapping around after the last one.
+    pub fn next(&mut self) -> Board {
+        self.index = (self.index + 1) % self.puzzles.len();
+        self.current()
+    }
+
+    /// Moves to the previous puzzle, wrapping around before the first one.
+    pub fn previous(&mut self) -> Board {
+        self.index = (self.index + self.puzzles.len() - 1) % self.puzzles.len();
+        self.current()
+    }
+
+    /// The number of the current puzzle, counting from 1.
+    pub fn number(&self) -> usize {
+        self.index + 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    /// Moves to the puzzle at `index`, counting from 0.
+    pub fn select(&mut self, index: usize) -> Board {
+        self.index = index.min(self.puzzles.len() - 1);
+        self.current()
+    }
+
+    /// Returns true if `board` is the current puzzle, maybe with entries.
+    pub fn is_current(&self, board: &Board) -> bool {
+        let mut givens = *board;
+        givens.clear_soft();
+        givens == self.current()
+    }
+
+    pub fn mark_solved(&mut self) {
+        self.solved[self.index] = true;
+    }
+
+    /// The puzzle at `index` as the browser lists it: its number, its
+    /// difficulty or `?` for puzzles without one solution, and a tick once
+    /// it is solved.
+    pub fn entry(&self, index: usize) -> String {
+        let difficulty = self.ratings[index].get_or_init(|| rate(&self.puzzles[index].0));
+        let difficulty = difficulty.map_or("?".to_string(), |difficulty| difficulty.to_string());
+        let tick = if self.solved[index] { " ✓" } else { "" };
+        format!("{} {difficulty}{tick}", index + 1)
+    }
+}
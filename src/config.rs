@@ -0,0 +1,324 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf}
+};
+
+use sdl2::pixels::Color;
+use serde::{Deserialize, Serialize};
+use sudoku_core::render::{Palette, Rgb};
+
+use crate::{camera, sys::{FONT_PATH, FPS, SCALE, TILE_SIZE}};
+
+/// A free API that hands out a random puzzle of any difficulty.
+const ONLINE_URL: &str = "https://sudoku-api.vercel.app/api/dosuku";
+
+/// The settings file, in the platform's directory for the app. Returns
+/// `None` if SDL can't find or create it.
+pub fn config_path() -> Option<PathBuf> {
+    let dir = sdl2::filesystem::pref_path("GardSvard", "sudoku-solver").ok()?;
+    Some(Path::new(&dir).join("config.toml"))
+}
+
+/// Settings read from `config.toml`. Anything the file leaves out keeps the
+/// built-in default.
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The size of one cell before scaling, in pixels.
+    pub tile_size: u32,
+    /// How many times larger than cells of `tile_size` the window opens.
+    pub scale: u32,
+    pub font_path: PathBuf,
+    /// How many frames a second the window is drawn at most.
+    pub fps: u32,
+    /// Whether frames wait for the display, so none are drawn that it can't show.
+    pub vsync: bool,
+    /// Whether the solver starts out showing every step.
+    pub visual_solving: bool,
+    /// Whether digits fade in and out as the solver shows its steps.
+    pub animate: bool,
+    /// How loud sound effects are, from 0 to 100.
+    pub volume: u8,
+    /// Whether sound effects start out muted.
+    pub muted: bool,
+    /// The webcam, as ffmpeg names input devices on this platform.
+    pub camera: String,
+    /// Where U downloads puzzles from, with `{difficulty}` standing for the
+    /// chosen difficulty if the API takes one.
+    pub online_url: String,
+    /// Whether placing a digit takes it out of the notes of the cells that see it.
+    pub auto_eliminate_notes: bool,
+    /// How many wrong digits end the game, or no limit if left out.
+    pub mistake_limit: Option<u32>,
+    /// Whether moving the cursor past an edge brings it in at the other side.
+    pub wrap_cursor: bool,
+    /// Whether h, j, k and l move the cursor, with counts, as in vim.
+    pub vim_keys: bool,
+    /// The theme the window starts with.
+    pub theme: Theme,
+    /// Colors to use instead of the theme's.
+    pub colors: Colors,
+    /// Keys for actions in place of their own, such as `solve = "Return"` or
+    /// `undo = ["Ctrl+Z", "Backspace"]`, from action names to SDL key names.
+    pub keys: BTreeMap<String, Keys>
+}
+
+/// One key or several for an action, where an empty list leaves it without any.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Keys {
+    One(String),
+    Many(Vec<String>)
+}
+
+impl Keys {
+    pub fn names(&self) -> &[String] {
+        match self {
+            Keys::One(name) => std::slice::from_ref(name),
+            Keys::Many(names) => names
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            tile_size: TILE_SIZE,
+            scale: SCALE,
+            font_path: PathBuf::from(FONT_PATH),
+            fps: FPS,
+            vsync: true,
+            visual_solving: true,
+            animate: true,
+            volume: 50,
+            muted: false,
+            camera: camera::default_device().to_string(),
+            online_url: ONLINE_URL.to_string(),
+            auto_eliminate_notes: true,
+            mistake_limit: None,
+            wrap_cursor: false,
+            vim_keys: false,
+            theme: Theme::Light,
+            colors: Theme::Light.colors(),
+            keys: BTreeMap::new()
+        }
+    }
+}
+
+/// The built-in sets of colors, switched between with ctrl+t. The
+/// colorblind one tells states apart by lightness and marks rather than
+/// by hue alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+    Colorblind
+}
+
+impl Theme {
+    pub fn next(self) -> Self {
+        match self {
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::Colorblind,
+            Theme::Colorblind => Theme::Light
+        }
+    }
+
+    pub fn colors(self) -> Colors {
+        match self {
+            Theme::Light => Colors {
+                background: Color::WHITE,
+                no_solution: Color::RGB(255, 220, 220),
+                several_solutions: Color::RGB(255, 250, 200),
+                grid: Color::BLACK,
+                digit: Color::BLACK,
+                soft_digit: Color::RGB(30, 70, 170),
+                given: Color::YELLOW,
+                given_cursor: Color::RGB(200, 200, 0),
+                cursor: Color::RGB(200, 200, 200),
+                region: Color::RGB(220, 240, 220),
+                changed: Color::RGB(200, 220, 255),
+                same_digit: Color::RGB(255, 220, 180),
+                peers: Color::RGB(238, 238, 245),
+                wrong: Color::RGB(220, 0, 0),
+                progress: Color::RGB(0, 160, 0),
+                banner: Color::RGB(60, 60, 60),
+                error: Color::RGB(200, 0, 0),
+                underline_givens: false,
+                box_border: 3
+            },
+            Theme::Dark => Colors {
+                background: Color::RGB(28, 28, 32),
+                no_solution: Color::RGB(70, 30, 30),
+                several_solutions: Color::RGB(65, 60, 25),
+                grid: Color::RGB(150, 150, 160),
+                digit: Color::RGB(230, 230, 230),
+                soft_digit: Color::RGB(120, 170, 255),
+                given: Color::RGB(75, 70, 30),
+                given_cursor: Color::RGB(110, 105, 40),
+                cursor: Color::RGB(80, 80, 90),
+                region: Color::RGB(35, 65, 40),
+                changed: Color::RGB(35, 55, 95),
+                same_digit: Color::RGB(95, 65, 35),
+                peers: Color::RGB(42, 42, 50),
+                wrong: Color::RGB(255, 90, 90),
+                progress: Color::RGB(0, 200, 0),
+                banner: Color::RGB(210, 210, 210),
+                error: Color::RGB(255, 90, 90),
+                underline_givens: false,
+                box_border: 3
+            },
+            // From the Okabe-Ito colors, with fills far apart in lightness
+            Theme::Colorblind => Colors {
+                background: Color::WHITE,
+                no_solution: Color::RGB(190, 190, 190),
+                several_solutions: Color::RGB(255, 240, 200),
+                grid: Color::BLACK,
+                digit: Color::BLACK,
+                soft_digit: Color::RGB(0, 114, 178),
+                given: Color::RGB(215, 215, 215),
+                given_cursor: Color::RGB(150, 150, 150),
+                cursor: Color::RGB(150, 205, 240),
+                region: Color::RGB(250, 240, 150),
+                changed: Color::RGB(205, 230, 250),
+                same_digit: Color::RGB(240, 190, 90),
+                peers: Color::RGB(242, 242, 242),
+                wrong: Color::RGB(213, 94, 0),
+                progress: Color::RGB(0, 158, 115),
+                banner: Color::BLACK,
+                error: Color::RGB(213, 94, 0),
+                underline_givens: true,
+                box_border: 3
+            }
+        }
+    }
+}
+
+/// Every color the board is drawn with, written as `[r, g, b]` in the file.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Colors {
+    #[serde(with = "rgb")]
+    pub background: Color,
+    /// The background when the board has no solution.
+    #[serde(with = "rgb")]
+    pub no_solution: Color,
+    /// The background when the board has more than one solution.
+    #[serde(with = "rgb")]
+    pub several_solutions: Color,
+    #[serde(with = "rgb")]
+    pub grid: Color,
+    /// Givens and digits written by hand.
+    #[serde(with = "rgb")]
+    pub digit: Color,
+    /// Digits placed by the solver, hints or reveals.
+    #[serde(with = "rgb")]
+    pub soft_digit: Color,
+    /// The fill of cells with a given.
+    #[serde(with = "rgb")]
+    pub given: Color,
+    /// The fill of a given under the cursor.
+    #[serde(with = "rgb")]
+    pub given_cursor: Color,
+    #[serde(with = "rgb")]
+    pub cursor: Color,
+    /// The row, column or box selected for filling.
+    #[serde(with = "rgb")]
+    pub region: Color,
+    /// Cells whose candidates the solver just changed.
+    #[serde(with = "rgb")]
+    pub changed: Color,
+    /// Other cells with the same digit as the cursor's.
+    #[serde(with = "rgb")]
+    pub same_digit: Color,
+    /// The row, column and box of the cursor.
+    #[serde(with = "rgb")]
+    pub peers: Color,
+    /// Entries found not to match the solution.
+    #[serde(with = "rgb")]
+    pub wrong: Color,
+    #[serde(with = "rgb")]
+    pub progress: Color,
+    #[serde(with = "rgb")]
+    pub banner: Color,
+    /// The banner saying there is no solution.
+    #[serde(with = "rgb")]
+    pub error: Color,
+    /// Whether givens are underlined, so they stand out without their fill.
+    pub underline_givens: bool,
+    /// How many pixels wide the borders around the boxes and the board are.
+    pub box_border: u32
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Theme::Light.colors()
+    }
+}
+
+impl Colors {
+    /// The colors the board itself is drawn with.
+    pub fn palette(&self) -> Palette {
+        Palette {
+            grid: to_rgb(self.grid),
+            digit: to_rgb(self.digit),
+            soft_digit: to_rgb(self.soft_digit),
+            given: to_rgb(self.given),
+            given_cursor: to_rgb(self.given_cursor),
+            cursor: to_rgb(self.cursor),
+            region: to_rgb(self.region),
+            changed: to_rgb(self.changed),
+            same_digit: to_rgb(self.same_digit),
+            peers: to_rgb(self.peers),
+            wrong: to_rgb(self.wrong),
+            progress: to_rgb(self.progress),
+            underline_givens: self.underline_givens,
+            box_border: self.box_border
+        }
+    }
+}
+
+pub fn to_rgb(color: Color) -> Rgb {
+    Rgb(color.r, color.g, color.b)
+}
+
+/// Colors as `[r, g, b]`.
+mod rgb {
+    use sdl2::pixels::Color;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        (color.r, color.g, color.b).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let (r, g, b) = <(u8, u8, u8)>::deserialize(deserializer)?;
+        Ok(Color::RGB(r, g, b))
+    }
+}
+
+/// Reads the settings in `path`, or the defaults if there is no such file.
+pub fn load(path: &Path) -> Result<Config, Box<dyn Error>> {
+    match fs::read_to_string(path) {
+        Ok(text) => {
+            // Colors left out of `[colors]` come from the chosen theme rather than the light one
+            let mut table: toml::Table = toml::from_str(&text)?;
+            let theme = match table.get("theme") {
+                Some(theme) => theme.clone().try_into()?,
+                None => Theme::Light
+            };
+            let colors = table.entry("colors").or_insert_with(|| toml::Table::new().into());
+            if let (toml::Value::Table(colors), toml::Value::Table(defaults)) = (colors, toml::Value::try_from(theme.colors())?) {
+                for (name, color) in defaults {
+                    colors.entry(name).or_insert(color);
+                }
+            }
+            Ok(table.try_into()?)
+        },
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(err) => Err(err.into())
+    }
+}
@@ -0,0 +1,58 @@
+use std::{error::Error, fs::File, io::BufWriter, path::Path};
+
+use qrcode::{types::QrError, Color, QrCode};
+use sdl2::{pixels::PixelFormatEnum, rect::Rect};
+use sudoku_core::{to_share_code, Board, SHARE_PREFIX};
+
+use crate::sys::SdlContext;
+
+/// Where the export key writes a picture of the board.
+pub const PNG_PATH: &str = "board.png";
+
+/// Writes the board in the frame drawn so far to `path` as a PNG, at the
+/// window's full resolution. Call this before the frame is presented.
+pub fn save_png(ctx: &SdlContext, path: &Path) -> Result<(), Box<dyn Error>> {
+    // Just the board, leaving out the panel beside it
+    let size = (ctx.board_size() as f32 * ctx.canvas.scale().0) as u32;
+    let pixels = ctx.canvas.read_pixels(Rect::new(0, 0, size, size), PixelFormatEnum::RGB24)?;
+
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), size, size);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&pixels)?;
+    Ok(())
+}
+
+/// Pixels per module in a saved QR code.
+const QR_MODULE_SIZE: u32 = 8;
+/// The blank border around a QR code that scanners need, in modules.
+pub const QR_QUIET_ZONE: u32 = 4;
+
+/// The share link for `board` as a QR code.
+pub fn qr_code(board: &Board) -> Result<QrCode, QrError> {
+    QrCode::new(format!("{SHARE_PREFIX}{}", to_share_code(board)))
+}
+
+/// Writes the QR code of `board`'s share link to `path` as a black and
+/// white PNG.
+pub fn save_qr_png(board: &Board, path: &Path) -> Result<(), Box<dyn Error>> {
+    let code = qr_code(board)?;
+    let modules = code.width() as u32;
+    let size = (modules + 2 * QR_QUIET_ZONE) * QR_MODULE_SIZE;
+    let colors = code.to_colors();
+
+    let mut pixels = Vec::with_capacity((size * size) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let (mx, my) = ((x / QR_MODULE_SIZE).wrapping_sub(QR_QUIET_ZONE), (y / QR_MODULE_SIZE).wrapping_sub(QR_QUIET_ZONE));
+            let dark = mx < modules && my < modules && colors[(my * modules + mx) as usize] == Color::Dark;
+            pixels.push(if dark { 0 } else { 255 });
+        }
+    }
+
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), size, size);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&pixels)?;
+    Ok(())
+}
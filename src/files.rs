@@ -0,0 +1,90 @@
+use std::{error::Error, fs, io, path::Path};
+
+use sudoku_core::{from_share_code, parse_csv, parse_sdm, Board, SdkMetadata, SdkPuzzle, SHARE_PREFIX};
+
+/// Where Ctrl+S saves a board that wasn't opened from a `.sdk` file.
+pub const DEFAULT_SAVE_PATH: &str = "puzzle.sdk";
+
+/// What a file given on the command line contained.
+pub enum Loaded {
+    Puzzle(Box<SdkPuzzle>),
+    /// Puzzles with the labels given to them in the file, if any.
+    Collection(Vec<(Board, Option<String>)>)
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+}
+
+/// Returns true if `path` has the `.sdk` extension.
+pub fn is_sdk(path: &Path) -> bool {
+    has_extension(path, "sdk")
+}
+
+/// Reads the puzzles in `path`.
+///
+/// `.sdk` files are read with their metadata, and `.sdm`, `.csv` and `.tsv`
+/// files as a collection. Any other file is read as a board on one line or
+/// laid out as a grid, or failing that as a list with one puzzle per line
+/// like a `.sdm` file. A directory is read as a collection of every puzzle
+/// in the files directly in it.
+pub fn load(path: &Path) -> Result<Loaded, Box<dyn Error>> {
+    if path.is_dir() {
+        return load_dir(path);
+    }
+    let text = fs::read_to_string(path)?;
+    if is_sdk(path) {
+        Ok(Loaded::Puzzle(Box::new(SdkPuzzle::parse(&text)?)))
+    } else if has_extension(path, "sdm") {
+        Ok(Loaded::Collection(parse_sdm(&text)?.into_iter().map(|board| (board, None)).collect()))
+    } else if has_extension(path, "csv") || has_extension(path, "tsv") {
+        Ok(Loaded::Collection(parse_csv(&text)?.into_iter().map(|puzzle| (puzzle.board, puzzle.label)).collect()))
+    } else {
+        match text.parse() {
+            Ok(board) => Ok(Loaded::Puzzle(Box::new(SdkPuzzle { board, metadata: SdkMetadata::default() }))),
+            // Report why it isn't one board rather than why it isn't a list
+            Err(err) => match parse_sdm(&text) {
+                Ok(puzzles) if puzzles.len() > 1 => Ok(Loaded::Collection(puzzles.into_iter().map(|board| (board, None)).collect())),
+                _ => Err(err.into())
+            }
+        }
+    }
+}
+
+/// Reads the puzzles in every file in `dir` in order of their names, each
+/// one labelled with its file's name unless its collection labels it.
+/// Files that aren't puzzles are left out.
+fn load_dir(dir: &Path) -> Result<Loaded, Box<dyn Error>> {
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+    let mut puzzles = Vec::new();
+    for path in paths {
+        let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+        match load(&path) {
+            Ok(Loaded::Puzzle(puzzle)) => puzzles.push((puzzle.board, name)),
+            Ok(Loaded::Collection(collection)) => puzzles.extend(collection.into_iter().map(|(board, label)| (board, label.or_else(|| name.clone())))),
+            Err(_) => ()
+        }
+    }
+    Ok(Loaded::Collection(puzzles))
+}
+
+/// Reads a board typed or pasted in by the user: a share code, or a board on
+/// one line or laid out as a grid.
+pub fn parse_board(text: &str) -> Result<Board, Box<dyn Error>> {
+    if text.trim().starts_with(SHARE_PREFIX) {
+        return Ok(from_share_code(text)?);
+    }
+    match text.parse() {
+        Ok(board) => Ok(board),
+        // A code without the prefix, or else the board's own error
+        Err(err) => from_share_code(text).map_err(|_| err.into())
+    }
+}
+
+pub fn save_sdk(path: &Path, puzzle: &SdkPuzzle) -> io::Result<()> {
+    fs::write(path, puzzle.to_sdk())
+}
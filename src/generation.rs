@@ -0,0 +1,67 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Arc
+    },
+    thread,
+    time::Instant
+};
+
+use sudoku_core::{generate_rated, Board, Difficulty, Rng};
+
+enum Message {
+    Attempt(usize),
+    Done(Box<Board>)
+}
+
+/// A puzzle being generated on a worker thread, so the window stays responsive.
+pub struct Generation {
+    pub difficulty: Difficulty,
+    /// The attempt the worker is on, counting from 1.
+    pub attempt: usize,
+    started: Instant,
+    receiver: Receiver<Message>,
+    cancel: Arc<AtomicBool>
+}
+
+impl Generation {
+    pub fn start(difficulty: Difficulty) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let worker_cancel = Arc::clone(&cancel);
+        thread::spawn(move || {
+            let mut rng = Rng::from_entropy();
+            let generated = generate_rated(difficulty, &mut rng, &worker_cancel, |attempt| {
+                let _ = sender.send(Message::Attempt(attempt));
+            });
+            if let Some(generated) = generated {
+                let _ = sender.send(Message::Done(Box::new(generated.puzzle)));
+            }
+        });
+
+        Self { difficulty, attempt: 0, started: Instant::now(), receiver, cancel }
+    }
+
+    /// Takes in everything the worker has sent so far, returning the puzzle once it is done.
+    pub fn poll(&mut self) -> Option<Board> {
+        for message in self.receiver.try_iter() {
+            match message {
+                Message::Attempt(attempt) => self.attempt = attempt,
+                Message::Done(board) => return Some(*board)
+            }
+        }
+        None
+    }
+
+    /// Tells the worker to stop after its current attempt.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// A spinning line to show that the worker is busy.
+    pub fn spinner(&self) -> char {
+        ['|', '/', '-', '\\'][(self.started.elapsed().as_millis() / 150 % 4) as usize]
+    }
+}
@@ -0,0 +1,44 @@
+use sudoku_core::{Board, Notes};
+
+/// How many edits can be undone before the oldest are forgotten.
+const MAX_UNDO: usize = 1000;
+
+/// The board and notes as they were at some point.
+pub type Snapshot = (Board, Notes);
+
+/// The states before each edit, for undoing them, and the states undone, for
+/// redoing them until something new is edited.
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers `before` as the state an edit started from.
+    pub fn record(&mut self, before: Snapshot) {
+        if self.undo.len() == MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    /// The state before the latest edit, if any, with `current` kept for redoing.
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// The state the latest undo went back from, if nothing was edited since.
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+}
@@ -0,0 +1,65 @@
+use sudoku_core::Board;
+
+/// A puzzle written as one line of 81 cells, with the name it is known by, if any.
+type Puzzle = (&'static str, Option<&'static str>);
+
+/// The shelves of the built-in library with their puzzles. The rated shelves
+/// hold puzzles the solver rates at that difficulty.
+pub const LIBRARY: [(&str, &[Puzzle]); 6] = [
+    ("Easy", &[
+        ("8..9.3....39..8.5...........7..6.....5.4..91..1..2.54..4......5.....1...2.5..4.3.", None),
+        (".4..6...9..........78..3....2.....8...4.8.3.6...627..51..5....4...3.69.....4....8", None),
+        ("8...2473.6.3.......2..5........7.....31..2..6.6...921.78.....65....8.9..3......2.", None),
+        ("..6.8731..........4....56...3...9.....76......917.3..6....7824......1..3.5.4..9..", None),
+        ("4......6....79.8....5.14.3.1...45.....7...58..3..7.......13..7......9....58.2.3..", None),
+        ("....53.9......9..6.791......38.1..2.......8......37.5....4....54..76......5.2.41.", None),
+        (".4......3...3.5....2....17.4..82..95.8.7..6....95........4.9...6........3..2..7.6", None),
+        (".4.....82.718...9........6..9.......6.5.3.21....4....3..85.....3....7...9.....14.", None)
+    ]),
+    ("Medium", &[
+        ("..5.93.1..61.7.....9.1....8.......876..91..........2..43....7...5.2.....98...73..", None),
+        ("9....2..415...7.2.......3..8.5.....62....14.....6..27...4.7.........39..6...9...5", None),
+        ("..291............5.1.63....85...6..97...5......4..87..2.....8..5....2....39.....4", None),
+        (".4.........8..1...3....5..2...........9...8..8..54..9...7.93.1.4..6...8.6..2...7.", None),
+        ("........812..7.......8.93.....42..3...6..3.8...7..642..3...7....6.......4.95..6..", None),
+        ("...3...414...1.6.....2.......54...23.93........8....1..79........4.7.259.....57.6", None),
+        ("..17.96..4......5.......9.2.4..........5..78...51.8...8.6.9..1...9..35.......6...", None),
+        ("6.5....7..1....8.4.9...12....4.23......8.........14.....31.869.....7..8.2...4.5..", None)
+    ]),
+    ("Hard", &[
+        ("5.3.......8..7....2....8.64..6.5.......4.......4...3.89..1..7.23....2..9..8..9...", None),
+        ("..54...3..6..8........9.2.4......7..7.321..9....3....1.......8..5...9...98..75...", None),
+        ("..87..92..........2549...........7.5....8..696.2...48.4.3.......7.6...5..1..3....", None),
+        ("7584..63.....6...1....5.4....7..4...32.9........67......6.....24.1...7..2.....5.4", None),
+        ("..586..9.23............2.1.9...4..5..48.93..6.........7..9....3..4.5..78..6......", None),
+        (".7..9........7..3.3.......8.1...5..29..8..1....7.....5.....4...7.1.3..6...8..2..3", None),
+        (".857..9.1.1............57.......2....3.9..8..5.41....2.....4...1....3.9.....6.3.5", None),
+        ("..7..38...4.18.29....9........4.81....6.......1.7....58.3.....6......7...2....4.1", None)
+    ]),
+    ("Expert", &[
+        ("..234.....7...8.398......52..1.....46.........8..5..7...9.6.7.....7.1.6....8.9...", None),
+        ("1.6..4.....9.....7.........5...7...4.4...3.7....2..1.8..1.8....9..4.7.1.3.4.9.8.5", None),
+        ("..28...6..7.5..14...9.17...........7.....1.3.3........5.8...2..16.7.2.5..3..5...8", None),
+        ("84....91.2.13....4......7...841.9..5..24.........2..7.3...5...1...23...........9.", None),
+        ("..7.....2.....8......35.1..2......3.63..4..1.......4.6.5.7.....34.8...2.9.143....", None),
+        (".....9..79...1..2.3...7.........48....61......7.5....3.1.3.....26....3...8..2.19.", None),
+        ("7....65.3...45.9.......7..281....7..2......84...57.....9.86..251.....4...6...4...", None),
+        (".......7..1.6.7..3.9.4.32.....9..1.75.8.4..9....25......91...3..4.3..5........4..", None)
+    ]),
+    ("17 clues", &[
+        ("4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......", None),
+        ("52...6.........7.13...........4..8..6......5...........418.........3..2...87.....", None)
+    ]),
+    ("Notorious", &[
+        ("1....7.9..3..2...8..96..5....53..9...1..8...26....4...3......1..4......7..7...3..", Some("AI Escargot")),
+        ("8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..", Some("Everest")),
+        ("1.......2.9.4...5...6...7...5.9.3.......7.......85..4.7.....6...3...9.8...2.....1", Some("Easter Monster"))
+    ])
+];
+
+/// The puzzles on the library's shelf at `index`, with their names.
+pub fn shelf(index: usize) -> Vec<(Board, Option<String>)> {
+    LIBRARY[index].1.iter()
+        .map(|&(line, name)| (line.parse().expect("the library's puzzles are written right"), name.map(str::to_string)))
+        .collect()
+}
@@ -1,380 +1,2364 @@
 
-use std::{sync::OnceLock, collections::HashSet, error::Error};
+use std::{collections::{HashMap, VecDeque}, error::Error, fs, io::{self, Write}, path::{Path, PathBuf}, time::{Duration, Instant, SystemTime}};
 
+use animation::Animations;
+use camera::Camera;
+use collab::{Message, Session};
+use collection::Collection;
+use config::{Colors, Config};
+use files::Loaded;
 use fixtures::test_board;
-use sdl2::{event, keyboard::Keycode, pixels::Color, rect::{Point, Rect}, sys::xdg_surface, EventPump};
-use sys::{SdlContext, LOGICAL_HEIGHT, LOGICAL_WIDTH, SCALE, TILE_SIZE};
+use replay::{Event, Player, Recorder};
+use save::SavedGame;
+use sound::{Effect, Sounds};
+use timer::Timer;
+use serde::{Deserialize, Serialize};
+use generation::Generation;
+use achievements::Achievements;
+use history::History;
+use ocr::{Corners, GrayImage, Templates};
+use qrcode::QrCode;
+use sdl2::{event::WindowEvent, keyboard::{Keycode, Mod}, mouse::MouseButton, pixels::{Color, PixelFormatEnum}, rect::Rect, EventPump};
+use sudoku_core::{render::{self, Button, Fade, Panel, Scene}, box_index, cell_name, count_solutions, get_pos, daily_puzzle, day_number, fill_unit, find_solution, hint, minimize, to_latex, to_share_code, to_svg, Board, Candidates, Coloring, Difficulty, Notes, SdkMetadata, SdkPuzzle, SvgOptions, SHARE_PREFIX, CellOrder, Progress, Reason, Rng, SolveLog, Solver, SolverOptions, Step, StepResult, Tile, Unit};
+use stats::{GameStats, Records};
+use sys::{Frame, Glyphs, SdlContext, SdlSurface};
 
+mod achievements;
+mod animation;
+mod camera;
+mod cli;
+mod collab;
+mod collection;
+mod config;
+mod export;
+mod files;
+mod generation;
+mod history;
+mod library;
+mod ocr;
+#[cfg(feature = "online")]
+mod online;
+mod replay;
+mod save;
+mod server;
+mod sound;
+mod stats;
 mod sys;
+mod timer;
 mod fixtures;
 
-type Board = [[Tile; 9]; 9];
-
-fn numbers() -> &'static HashSet<u8> {
-    static NUMBERS: OnceLock<HashSet<u8>> = OnceLock::new();
-    NUMBERS.get_or_init(|| {
-       HashSet::from_iter(1..10)
-    })
-}
-
 fn main() -> Result<(), Box<dyn Error>>{
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("generate") => return cli::generate(&args[2..]),
+        Some("print") => return cli::print(&args[2..]),
+        Some("solve") => return cli::solve(&args[2..]),
+        Some("bench") => return cli::bench(&args[2..]),
+        Some("serve") => return server::serve(&args[2..]),
+        _ => ()
+    }
+
     let sdl = sdl2::init()?;
     let video = sdl.video()?;
-    let mut ctx = sys::init_sdl_systems(&sdl, &video)?;
+    let mut config = load_config();
+    let mut bindings = key_bindings(&config);
+    // Exports a picture of the starting board instead of opening the window
+    let png_path = flag_value(&args, "--png");
+    let svg_path = flag_value(&args, "--svg");
+    let latex_path = flag_value(&args, "--latex");
+    let qr_path = flag_value(&args, "--qr");
+    let hidden = png_path.is_some() || svg_path.is_some() || latex_path.is_some() || qr_path.is_some();
+    let mut ctx = sys::init_sdl_systems(&sdl, &video, hidden, config.tile_size, config.scale, config.vsync)?;
+    let clipboard = video.clipboard();
     let ttf = sdl2::ttf::init()?;
-    let font = sys::load_font(&ttf)?;
+    let mut font = sys::load_font(&ttf, &config.font_path, ctx.tile_size)?;
+    let glyph_creator = ctx.canvas.texture_creator();
+    let mut glyphs = Glyphs::new(&glyph_creator);
+    let mut drawn_frame = Frame::new(&glyph_creator);
+    // Print the solve log automatically whenever the solver stops
+    let print_log = args.iter().any(|arg| arg == "--log");
     
-    let mut board = [[Tile::Empty; 9]; 9];
     let mut cursor_index = (0, 0);
-    
+    // The colors the player marked cells with, which like the notes the solver never sees
+    let mut coloring = Coloring::new();
+    let autosave_path = save::autosave_path();
+    let records_path = stats::records_path();
+    let mut records = records_path.as_deref().and_then(|path| Records::load(path).ok()).unwrap_or_default();
+    let achievements_path = achievements::achievements_path();
+    let mut achievements = achievements_path.as_deref().and_then(|path| Achievements::load(path).ok()).unwrap_or_default();
+    // The `.sdk` file the board came from and its metadata, which Ctrl+S saves back to
+    let mut sdk_file: Option<(PathBuf, SdkMetadata)> = None;
+    // The puzzle pack being worked through, if one was opened
+    let mut collection = None;
+    // The day of the daily puzzle being played, if it is one
+    let mut daily = None;
+    let mut board = if let Some(path) = flag_value(&args, "--file") {
+        let opened = open_file(Path::new(path), &font)?;
+        (sdk_file, collection) = (opened.sdk_file, opened.collection);
+        opened.board
+    } else if let Some(puzzle) = positional_arg(&args) {
+        files::parse_board(puzzle)?
+    } else if args.iter().any(|arg| arg == "--daily") {
+        daily = Some(day_number(SystemTime::now()));
+        daily_puzzle(SystemTime::now())
+    } else if let Some(game) = autosave_path.as_deref().and_then(|path| save::load(path).ok()) {
+        cursor_index = (game.cursor.0.clamp(0, 8), game.cursor.1.clamp(0, 8));
+        coloring = game.coloring;
+        game.board
+    } else {
+        Board::new()
+    };
+
     let mut running = true;
     let mut solving = false;
-    let mut visual_solving = true;
-    let mut solving_idx = 0;
+    // Set by the single step key, and cleared once that step has been taken
+    let mut step_once = false;
+    // Whether the solver has stopped, either solved or stuck
+    let mut finished = false;
+    let mut unsolvable = false;
+    let mut visual_solving = config.visual_solving;
+    // Whether the board's share link is shown as a QR code over the board
+    let mut show_qr = false;
+    // A short message across the board, such as how many clues a dropped file had
+    let mut notice: Option<(String, Instant)> = None;
+    // Tells of newly unlocked achievements, over the stats too
+    let mut toast: Option<(String, Instant)> = None;
+    // Shown over the board while open, until a frame is read or it is closed
+    let mut camera: Option<Camera> = None;
+    // The candidates pencilled in by the player, which the solver never sees
+    let mut notes = Notes::new();
+    // The solution of the puzzle, found once it has just one, and kept
+    // through wrong entries until another puzzle is loaded
+    let mut solution: Option<Board> = None;
+    // Entries found not to match the solution, outlined until the board changes
+    let mut wrong: Vec<(i8, i8)> = Vec::new();
+    // Whether digits typed become givens, as when entering a puzzle, rather
+    // than entries that leave the givens alone. Starts on for an empty board
+    let mut editing = board.clues() == 0;
+    // Whether digits typed are pencilled in as notes rather than written
+    let mut note_mode = false;
+    // Whether a digit is picked first and then placed in each cell clicked,
+    // rather than a cell picked and then given a digit, with the digit picked
+    let mut digit_first = false;
+    let mut active_digit: Option<u8> = None;
+    // The board as it was when notes were last brought up to date with it
+    let mut noted_board = board;
+    // Edits to the board and notes, for undoing and redoing them
+    let mut history = History::new();
+    // How long the puzzle has been played, restarted whenever another is loaded
+    let mut timer = Timer::start();
+    // Digits written that don't match the solution, since the puzzle was loaded
+    let mut stats = GameStats::new();
+    // Switched between the built-in themes with a key, replacing any colors from the settings
+    let mut theme = config.theme;
+    let mut colors = config.colors;
+    // Cells picked along with the cursor, which digits, notes and removals
+    // apply to together. Empty when only the cursor is picked
+    let mut selection: Vec<(i8, i8)> = Vec::new();
+    let mut menu: Option<Menu> = None;
+    let mut browser: Option<Browser> = None;
+    // Whether the keys and modes are listed over the board
+    let mut show_help = false;
+    // The latest change to the board or notes, told in the status bar
+    let mut last_action: Option<String> = None;
+    let mut animations = Animations::new();
+    // Set when the player fills in the board correctly, showing how it went
+    // until another puzzle is loaded or the overlay is closed
+    let mut show_stats = false;
+    // The colors of a saved game being loaded, kept through the reset that comes with loading it
+    let mut saved_coloring: Option<Coloring> = None;
+    // The day of the daily puzzle being loaded, which becomes `daily` once it is
+    let mut loaded_daily = None;
+    let mut show_records = false;
+    // When clearing the board was first asked for, which a second press soon after confirms
+    let mut clear_requested: Option<Instant> = None;
+    // With vim keys, the count typed before a motion, and whether `r` is waiting for a digit
+    let mut count: i8 = 0;
+    let mut replacing = false;
+    // The cell the mouse's digit picker is open on, which is always the cursor
+    let mut picker: Option<(i8, i8)> = None;
+    // The row, column or box around the cursor that the fill key works on
+    let mut region = None;
+    // An index into `SPEEDS`
+    let mut speed = DEFAULT_SPEED;
+    // The fraction of a step owed from earlier frames
+    let mut step_debt = 0.0;
+    let mut solver_options = SolverOptions::default();
+    let mut solver = solver_options.build();
+    let mut last_step = None;
+    let mut log = SolveLog::new();
+    let mut progress = Progress::new(&board);
+    // Capped at 2, since only "none", "unique" and "several" matter to the user
+    let mut solutions = count_solutions(&board, 2);
+    let mut difficulty = Difficulty::Medium;
+    let mut generation: Option<Generation> = None;
+    #[cfg(feature = "online")]
+    let mut download: Option<online::Download> = None;
     
+    if let Some(path) = png_path {
+        return export_png(&board, &mut ctx, &font, &mut glyphs, &mut drawn_frame, &config.colors, Path::new(path));
+    }
+    if let Some(path) = svg_path {
+        let options = SvgOptions { candidates: args.iter().any(|arg| arg == "--candidates"), ..SvgOptions::default() };
+        fs::write(path, to_svg(&board, options))?;
+        return Ok(());
+    }
+    if let Some(path) = latex_path {
+        fs::write(path, to_latex(&board))?;
+        return Ok(());
+    }
+    if let Some(path) = qr_path {
+        return export::save_qr_png(&board, Path::new(path));
+    }
+    let mut sounds = Sounds::open(&sdl, config.volume, config.muted);
+
+    // A recorded session to play back, which takes the place of the keyboard until it ends
+    let mut player = None;
+    if let Some(path) = flag_value(&args, "--replay") {
+        let replay = replay::load(Path::new(path))?;
+        board = replay.board;
+        cursor_index = (replay.cursor.0.clamp(0, 8), replay.cursor.1.clamp(0, 8));
+        visual_solving = replay.visual_solving;
+        progress = Progress::new(&board);
+        solutions = count_solutions(&board, 2);
+        player = Some(Player::new(replay));
+    }
+    // A board shared with other players, who each have a cursor of their own
+    let session = if let Some(port) = flag_value(&args, "--host") {
+        let session = Session::host(port.parse()?)?;
+        println!("Hosting on port {port}; others can join with --join ADDRESS:{port}");
+        Some(session)
+    } else if let Some(address) = flag_value(&args, "--join") {
+        let session = Session::join(address)?;
+        println!("Joined {address} as player {}", session.player);
+        Some(session)
+    } else {
+        None
+    };
+    let mut players: HashMap<u8, (i8, i8)> = HashMap::new();
+    if let Some(session) = &session {
+        session.send(Message::Cursor { player: session.player, cursor: cursor_index });
+    }
+    let mut recorder = flag_value(&args, "--record").map(|path| (PathBuf::from(path), Recorder::new(board, cursor_index, visual_solving)));
+
+    let mut last_autosave = (Instant::now(), autosave_game(&board, cursor_index, &coloring));
+    let mut last_frame = Instant::now();
     while running {
-        if solving {
-            match solve(&mut board, solving_idx) {
-                BoardState::Solving(idx) => {
-                    if solving_idx != 80 {
-                        solving_idx = idx;
-                    } else {
+        let frame_start = Instant::now();
+        let frame_delta = frame_start - last_frame;
+        last_frame = frame_start;
+
+        // Visual solving is paced by the clock rather than by the frame rate,
+        // carrying over fractions of a step until they add up to a whole one
+        let steps = if step_once {
+            1
+        } else if !solving {
+            0
+        } else if visual_solving {
+            step_debt += SPEEDS[speed] as f64 * frame_delta.as_secs_f64();
+            let steps = step_debt as u32;
+            step_debt -= steps as f64;
+            steps
+        } else {
+            UNTHROTTLED_STEPS
+        };
+        step_once = false;
+        // Solving without visuals, even for a moment, means the solve wasn't watched
+        stats.watched &= visual_solving || !solving;
+
+        let stepped_from = board;
+        let mut backtracked = false;
+        for _ in 0..steps {
+            if finished {
+                break;
+            }
+            match advance(solver.as_mut(), &mut board, &mut progress, &mut log) {
+                StepResult::Solving(step @ Step::Unplace { .. }) => {
+                    backtracked = true;
+                    last_step = Some(step);
+                },
+                StepResult::Solving(step) => last_step = Some(step),
+                result => {
+                    solving = false;
+                    finished = true;
+                    unsolvable = matches!(result, StepResult::Unsolvable);
+                    last_step = None;
+                    if print_log {
+                        print!("{log}");
+                    }
+                }
+            }
+        }
+        
+        // One tick a frame at most, however many steps were taken back
+        if backtracked && visual_solving {
+            sounds.play(Effect::Backtrack);
+        }
+        if config.animate && visual_solving && board != stepped_from {
+            // Quick enough that a fade finishes before the next step
+            let duration = animation::FADE_TIME.min(Duration::from_secs_f64(1.0 / SPEEDS[speed] as f64));
+            animations.changed(&stepped_from, &board, duration);
+        }
+
+        let mut edited = false;
+        // Whether another puzzle took the place of the board, rather than it being edited
+        let mut loaded = false;
+        if let Some(puzzle) = generation.as_mut().and_then(Generation::poll) {
+            history.record((board, notes));
+            board = puzzle;
+            sdk_file = None;
+            loaded = true;
+            generation = None;
+            edited = true;
+            if let Some((_, recorder)) = &mut recorder {
+                recorder.record(Event::Board(Box::new(board), cursor_index));
+            }
+            if let Some(session) = &session {
+                session.send(Message::Board { player: session.player, board: Box::new(board) });
+            }
+        }
+
+        #[cfg(feature = "online")]
+        if let Some(result) = download.as_mut().and_then(online::Download::poll) {
+            download = None;
+            match result {
+                Ok(puzzle) => {
+                    history.record((board, notes));
+                    board = puzzle;
+                    sdk_file = None;
+                    loaded = true;
+                    solving = false;
+                    edited = true;
+                    if let Some((_, recorder)) = &mut recorder {
+                        recorder.record(Event::Board(Box::new(board), cursor_index));
+                    }
+                    if let Some(session) = &session {
+                        session.send(Message::Board { player: session.player, board: Box::new(board) });
+                    }
+                },
+                Err(err) => println!("Couldn't download a puzzle: {err}")
+            }
+        }
+
+        if let Some(open) = &mut camera
+            && !open.is_running()
+        {
+            println!("The camera stopped; check that ffmpeg is installed and that {} is a camera", config.camera);
+            camera = None;
+        }
+
+        let messages = session.as_ref().map(Session::poll).unwrap_or_default();
+        let mut render = !messages.is_empty();
+        for message in messages {
+            let before = board;
+            match message {
+                Message::Welcome { .. } => (),
+                Message::Joined { player } => {
+                    println!("Player {player} joined");
+                    // Bring them up to date, since only the host has everyone's latest
+                    if let Some(session) = session.as_ref().filter(|session| session.is_host()) {
+                        session.send(Message::Board { player: session.player, board: Box::new(board) });
+                        session.send(Message::Cursor { player: session.player, cursor: cursor_index });
+                        for (&player, &cursor) in &players {
+                            session.send(Message::Cursor { player, cursor });
+                        }
+                    }
+                },
+                Message::Left { player } => {
+                    match player {
+                        collab::HOST => println!("The host left"),
+                        _ => println!("Player {player} left")
+                    }
+                    players.remove(&player);
+                },
+                // The host brings new players up to date on everyone, them included
+                Message::Cursor { player, cursor } if session.as_ref().is_some_and(|session| session.player != player) => {
+                    players.insert(player, cursor);
+                },
+                Message::Cursor { .. } => (),
+                Message::Action { player, cursor, action, given } => {
+                    let tile = match action {
+                        Action::Write(num @ 1..=9) if given => Tile::Hard(num),
+                        Action::Write(num @ 1..=9) => Tile::Soft(num),
+                        Action::Remove => Tile::Empty,
+                        _ => continue
+                    };
+                    let pos = (cursor.0.clamp(0, 8) as usize, cursor.1.clamp(0, 8) as usize);
+                    // Only an edit of the puzzle touches its givens
+                    if given || !matches!(board.get(pos), Tile::Hard(_)) {
+                        board.set(pos, tile);
+                    }
+                    players.insert(player, cursor);
+                    solving = false;
+                    edited = true;
+                },
+                Message::Board { board: shared, .. } => {
+                    board = *shared;
+                    sdk_file = None;
+                    loaded = true;
+                    solving = false;
+                    edited = true;
+                }
+            }
+            if board != before && let Some((_, recorder)) = &mut recorder {
+                recorder.record(Event::Board(Box::new(board), cursor_index));
+            }
+        }
+
+        let mut dropped = Vec::new();
+        let actions = handle_input(&mut ctx.events, &mut running, &mut dropped, &bindings, ctx.tile_size);
+        for path in dropped {
+            match open_file(&path, &font) {
+                Ok(opened) => {
+                    history.record((board, notes));
+                    board = opened.board;
+                    (sdk_file, collection) = (opened.sdk_file, opened.collection);
+                    loaded = true;
+                    solving = false;
+                    edited = true;
+                    notice = Some((format!("{} clues read", board.clues()), Instant::now()));
+                    if let Some((_, recorder)) = &mut recorder {
+                        recorder.record(Event::Board(Box::new(board), cursor_index));
+                    }
+                    if let Some(session) = &session {
+                        session.send(Message::Board { player: session.player, board: Box::new(board) });
+                    }
+                },
+                Err(err) => {
+                    println!("Couldn't open {}: {err}", path.display());
+                    notice = Some(("Couldn't read the file".to_string(), Instant::now()));
+                }
+            }
+        }
+        notice.take_if(|(_, shown)| shown.elapsed() >= NOTICE_TIME);
+        toast.take_if(|(_, shown)| shown.elapsed() >= TOAST_TIME);
+        let mut events: VecDeque<Event> = match &mut player {
+            // Keys do nothing while a replay plays, apart from closing the window
+            Some(player) => player.due().into(),
+            // Digits typed pick the digit to place when it comes first
+            None => actions.into_iter().map(|action| match action {
+                Action::Write(num) if digit_first => Action::PickDigit(num),
+                action => action
+            }).map(Event::Action).collect()
+        };
+        if player.as_ref().is_some_and(Player::is_done) {
+            player = None;
+            println!("The replay has ended");
+        }
+        render |= !events.is_empty();
+        while let Some(event) = events.pop_front() {
+            let action = match event {
+                Event::Action(action) => action,
+                Event::Board(replaced, cursor) => {
+                    board = *replaced;
+                    cursor_index = (cursor.0.clamp(0, 8), cursor.1.clamp(0, 8));
+                    sdk_file = None;
+                    loaded = true;
+                    solving = false;
+                    edited = true;
+                    continue;
+                },
+                Event::Seed(seed) => {
+                    solver_options.seed = seed;
+                    solving = false;
+                    edited = true;
+                    continue;
+                }
+            };
+            // The help stays up until any key or click closes it
+            let action = match action {
+                Action::ToggleHelp => {
+                    show_help = !show_help;
+                    Action::Nothing
+                },
+                Action::Resize => action,
+                _ if show_help || show_records => {
+                    (show_help, show_records) = (false, false);
+                    Action::Nothing
+                },
+                action => action
+            };
+            // The browser lists the collection's puzzles in place of the board like
+            // the menu. Up and down choose one, page up and down a page at a time,
+            // and Enter or a click opens it
+            let browsed_len = browser.as_ref().and_then(|open| open.browsed(&collection)).map(Collection::len);
+            let action = match (browser.as_mut(), browsed_len) {
+                (Some(open), Some(len)) => {
+                    let last = len - 1;
+                    let chosen = match action {
+                        Action::Move(_, y) => {
+                            open.selected = open.selected.saturating_add_signed(y.signum() as isize).min(last);
+                            None
+                        },
+                        Action::NextPuzzle => {
+                            open.selected = (open.selected + BROWSER_ROWS).min(last);
+                            None
+                        },
+                        Action::PreviousPuzzle => {
+                            open.selected = open.selected.saturating_sub(BROWSER_ROWS);
+                            None
+                        },
+                        Action::Capture | Action::Solve => Some(Action::OpenPuzzle(open.selected)),
+                        Action::Click(x, y) => {
+                            let first = open.first(len);
+                            let rows = BROWSER_ROWS.min(len - first);
+                            render::menu_item((x, y), rows, ctx.tile_size).map(|row| Action::OpenPuzzle(first + row))
+                        },
+                        Action::Cancel | Action::BrowsePuzzles => Some(Action::Nothing),
+                        Action::Resize | Action::Quit => Some(action),
+                        _ => None
+                    };
+                    // A library shelf only takes the collection's place once a puzzle is opened from it
+                    if matches!(chosen, Some(Action::OpenPuzzle(_))) && let Some(shelf) = open.shelf.take() {
+                        collection = Some(shelf);
+                    }
+                    match chosen {
+                        Some(chosen) => {
+                            if open.paused_timer {
+                                timer.toggle_pause();
+                            }
+                            browser = None;
+                            chosen
+                        },
+                        None => Action::Nothing
+                    }
+                },
+                _ => action
+            };
+            // Escape with nothing to cancel opens the menu, hiding the board and
+            // pausing the timer. Up and down choose an item, Enter or a click takes it
+            let busy = show_stats || generation.is_some() || camera.is_some() || picker.is_some() || active_digit.is_some() || !selection.is_empty();
+            #[cfg(feature = "online")]
+            let busy = busy || download.is_some();
+            let action = match menu.as_mut() {
+                None if action == Action::Cancel && !busy => {
+                    let paused_timer = !timer.is_paused() && !timer.is_stopped();
+                    if paused_timer {
+                        timer.toggle_pause();
+                    }
+                    menu = Some(Menu { items: &MENU, selected: 0, paused_timer });
+                    Action::Nothing
+                },
+                None => action,
+                Some(open) => {
+                    let chosen = match action {
+                        Action::Move(_, y) => {
+                            open.selected = (open.selected as i8 + y.signum()).rem_euclid(open.items.len() as i8) as usize;
+                            None
+                        },
+                        Action::Capture | Action::Solve => Some(open.items[open.selected].1),
+                        Action::Click(x, y) => render::menu_item((x, y), open.items.len(), ctx.tile_size).map(|item| open.items[item].1),
+                        Action::Cancel => Some(MenuItem::Take(Action::Nothing)),
+                        _ => None
+                    };
+                    let taken = match chosen {
+                        Some(MenuItem::NewGame) => {
+                            // Starting on the difficulty chosen last
+                            open.items = &NEW_GAME_MENU;
+                            open.selected = NEW_GAME_MENU.iter().position(|&(_, item)| item == MenuItem::Generate(difficulty)).unwrap_or(0);
+                            None
+                        },
+                        Some(MenuItem::Back) => {
+                            (open.items, open.selected) = (&MENU, 0);
+                            None
+                        },
+                        Some(MenuItem::Generate(chosen)) => {
+                            difficulty = chosen;
+                            Some(Action::Generate)
+                        },
+                        Some(MenuItem::Library) => {
+                            (open.items, open.selected) = (&LIBRARY_MENU, 0);
+                            None
+                        },
+                        Some(MenuItem::Shelf(shelf)) => {
+                            // The browser takes over keeping the timer paused
+                            let shelf = Collection::new(library::shelf(shelf));
+                            browser = Some(Browser { selected: 0, paused_timer: open.paused_timer, shelf });
+                            open.paused_timer = false;
+                            Some(Action::Nothing)
+                        },
+                        Some(MenuItem::Stats) => {
+                            show_records = true;
+                            Some(Action::Nothing)
+                        },
+                        Some(MenuItem::Take(action)) => Some(action),
+                        None => None
+                    };
+                    match taken {
+                        Some(taken) => {
+                            if open.paused_timer {
+                                timer.toggle_pause();
+                            }
+                            menu = None;
+                            taken
+                        },
+                        None => Action::Nothing
+                    }
+                }
+            };
+            // Enter on the celebration starts another puzzle
+            let action = match action {
+                Action::Capture if show_stats && camera.is_none() => Action::Generate,
+                action => action
+            };
+            // The panel's buttons stand in for keys, with a right click on a digit noting it
+            let action = match action {
+                Action::Click(x, y) | Action::RightClick(x, y) => match render::panel_button((x, y), ctx.tile_size) {
+                    Some(Button::Digit(digit)) if matches!(action, Action::RightClick(..)) => Action::ToggleNote(digit),
+                    Some(Button::Digit(digit)) if digit_first => Action::PickDigit(digit),
+                    Some(Button::Digit(digit)) => Action::Write(digit),
+                    Some(Button::Erase) => Action::Remove,
+                    Some(Button::Notes) => Action::ToggleNoteMode,
+                    Some(Button::Undo) => Action::Undo,
+                    Some(Button::Hint) => Action::Hint,
+                    Some(Button::Solve) => Action::Solve,
+                    None => action
+                },
+                action => action
+            };
+            // A click on the picker writes its digit and a right click notes it,
+            // while a click anywhere else on the board opens it there. Picking
+            // the digit first, a click places it instead, as does Enter
+            let action = match action {
+                Action::Click(x, y) | Action::RightClick(x, y) if digit_first => match (cell_at(x, y, ctx.tile_size), active_digit) {
+                    (Some((x, y)), Some(digit)) => {
+                        let place = if matches!(action, Action::Click(..)) { Action::Write(digit) } else { Action::ToggleNote(digit) };
+                        events.push_front(Event::Action(place));
+                        Action::Select(x, y)
+                    },
+                    (Some((x, y)), None) => Action::Select(x, y),
+                    (None, _) => Action::Nothing
+                },
+                Action::Capture if digit_first && camera.is_none() => active_digit.map_or(Action::Nothing, Action::Write),
+                Action::Click(x, y) => match picker.and_then(|cell| render::picker_digit(cell, (x, y), ctx.tile_size)) {
+                    Some(digit) => {
+                        picker = None;
+                        Action::Write(digit)
+                    },
+                    None => {
+                        picker = cell_at(x, y, ctx.tile_size);
+                        picker.map_or(Action::Nothing, |(x, y)| Action::Select(x, y))
+                    }
+                },
+                Action::RightClick(x, y) => match picker.and_then(|cell| render::picker_digit(cell, (x, y), ctx.tile_size)) {
+                    Some(digit) => Action::ToggleNote(digit),
+                    None => Action::Nothing
+                },
+                action => action
+            };
+            // Vim keys type counts and wait for `r`'s digit before anything happens
+            let action = match action {
+                Action::Count(num) if replacing && num > 0 => {
+                    replacing = false;
+                    Action::Write(num)
+                },
+                Action::Count(num) => {
+                    count = (count * 10 + num as i8).min(8);
+                    Action::Nothing
+                },
+                Action::ReplaceNext => {
+                    replacing = true;
+                    Action::Nothing
+                },
+                Action::Move(x, y) => {
+                    let times = count.max(1);
+                    count = 0;
+                    Action::Move(x * times, y * times)
+                },
+                action => {
+                    (count, replacing) = (0, false);
+                    action
+                }
+            };
+            let game_over = config.mistake_limit.is_some_and(|limit| stats.mistakes >= limit);
+            let action = match action {
+                Action::Write(num) if note_mode => Action::ToggleNote(num),
+                // The board stays as it was once the mistakes run out, until another puzzle is loaded
+                Action::Write(_) | Action::Remove | Action::Reveal | Action::Hint if game_over => Action::Nothing,
+                action => action
+            };
+            let before = (board, cursor_index, solver_options.seed);
+            let snapshot = (board, notes);
+            let picked: Vec<(usize, usize)> = match selection.is_empty() {
+                true => vec![(cursor_index.0 as usize, cursor_index.1 as usize)],
+                false => selection.iter().map(|&(x, y)| (x as usize, y as usize)).collect()
+            };
+            // Moving the cursor on its own leaves the rest of the selection behind
+            if matches!(action, Action::Move(..) | Action::Select(..) | Action::NextEmpty | Action::PreviousEmpty | Action::Cancel) {
+                selection.clear();
+            }
+            match action {
+                Action::Move(x, y) if config.wrap_cursor => cursor_index = ((cursor_index.0 + x).rem_euclid(9), (cursor_index.1 + y).rem_euclid(9)),
+                Action::Move(x, y) => cursor_index = ((cursor_index.0 + x).clamp(0, 8), (cursor_index.1 + y).clamp(0, 8)),
+                Action::NextEmpty | Action::PreviousEmpty => {
+                    // Searches in reading order, around the end of the board and back to the start
+                    let here = cursor_index.1 as usize * 9 + cursor_index.0 as usize;
+                    let step = if matches!(action, Action::NextEmpty) { 1 } else { 80 };
+                    let empty = (1..81).map(|i| (here + i * step) % 81).map(get_pos).find(|&pos| board.get(pos) == Tile::Empty);
+                    if let Some((x, y)) = empty {
+                        cursor_index = (x as i8, y as i8);
+                    }
+                },
+                Action::Select(x, y) => cursor_index = (x, y),
+                Action::ExtendSelection(x, y) | Action::ExtendTo(x, y) => {
+                    let target = match action {
+                        Action::ExtendTo(..) => (x, y),
+                        _ => ((cursor_index.0 + x).clamp(0, 8), (cursor_index.1 + y).clamp(0, 8))
+                    };
+                    for cell in [cursor_index, target] {
+                        if !selection.contains(&cell) {
+                            selection.push(cell);
+                        }
+                    }
+                    cursor_index = target;
+                },
+                // Edit mode is only left, by hand or to solve, for givens that make a puzzle
+                Action::ToggleEditMode | Action::Solve if editing => match givens_problem(&board) {
+                    None => {
+                        editing = false;
+                        wrong.clear();
+                        match action {
+                            Action::Solve => solving = !solving && board.is_valid(),
+                            _ => notice = Some(("Playing".to_string(), Instant::now()))
+                        }
+                    },
+                    Some((text, conflicts)) => {
+                        wrong = conflicts;
+                        notice = Some((text.to_string(), Instant::now()));
+                    }
+                },
+                Action::Solve => {
+                    // Pauses and resumes without resetting the solver
+                    if board.is_valid() {
+                        solving = !solving;
+                    }
+                },
+                Action::StepOnce => {
+                    if board.is_valid() && !solving {
+                        step_once = true;
+                    }
+                },
+                Action::StepBack => {
+                    // Pauses the solve, which then replays the log until it catches up
+                    solving = false;
+                    if let Some(step) = log.rewind(&mut board) {
+                        progress.unrecord(step);
+                        last_step = Some(step.clone());
+                        finished = false;
+                        unsolvable = false;
+                    }
+                },
+                Action::Write(num) if editing => {
+                    for &pos in &picked {
+                        board.set(pos, Tile::Hard(num));
+                    }
+                    sounds.play(Effect::Place);
+                    edited = true;
+                },
+                Action::Write(num) => {
+                    // Each selected cell the digit is wrong in counts as a mistake
+                    let mistakes_before = stats.mistakes;
+                    for &pos in picked.iter().filter(|&&pos| !matches!(before.0.get(pos), Tile::Hard(_))) {
+                        if solution.is_some_and(|solution| solution.get(pos).digit() != Some(num)) {
+                            stats.mistakes += 1;
+                        }
+                        board.set(pos, Tile::Soft(num));
+                    }
+                    sounds.play(if stats.mistakes > mistakes_before { Effect::Error } else { Effect::Place });
+                    if config.mistake_limit.is_some_and(|limit| stats.mistakes >= limit) {
+                        timer.stop();
+                        println!("Game over after {} mistakes", stats.mistakes);
+                        stats.finish(&board, None);
+                        add_record(&mut records, records_path.as_deref(), &stats, false, timer.elapsed(), daily);
+                        toast = unlock(&mut achievements, achievements_path.as_deref(), &stats, false, timer.elapsed(), &records).or(toast);
+                    }
+                    edited = true;
+                },
+                Action::Remove => {
+                    for &pos in picked.iter().filter(|&&pos| editing || !matches!(before.0.get(pos), Tile::Hard(_))) {
+                        board.set(pos, Tile::Empty);
+                    }
+                    edited = true;
+                },
+                Action::Undo | Action::Redo => {
+                    let restored = match action {
+                        Action::Undo => history.undo((board, notes)),
+                        _ => history.redo((board, notes))
+                    };
+                    if let Some((restored, restored_notes)) = restored {
+                        (board, notes) = (restored, restored_notes);
+                        // Digits brought back shouldn't clear the notes brought back with them
+                        noted_board = board;
+                        solving = false;
+                        edited = true;
+                    }
+                },
+                Action::Hint => match hint(&board, &notes, solver_options.techniques) {
+                    Some(step) => {
+                        println!("Hint: {step}");
+                        match &step {
+                            Step::Place { reason: Reason::Logic(technique), .. } | Step::Eliminate { technique, .. } => {
+                                notice = Some((technique.to_string(), Instant::now()));
+                            },
+                            _ => ()
+                        }
+                        if let Step::Eliminate { eliminations, .. } = &step {
+                            // Noted so the next hint moves on, starting from every candidate in cells without notes
+                            for &(pos, mask) in eliminations {
+                                let noted = if notes.get(pos) == 0 { board.candidates(pos) } else { notes.get(pos) };
+                                notes.set(pos, noted & !mask);
+                            }
+                        }
+                        step.apply(&mut board);
+                        if board != before.0 {
+                            solving = false;
+                            edited = true;
+                        }
+                        // Tints the cells whose candidates the hint took out
+                        last_step = Some(step);
+                        stats.hints += 1;
+                    },
+                    None => notice = Some(("No hint found".to_string(), Instant::now()))
+                },
+                Action::Reveal => match solution {
+                    Some(solution) => {
+                        let pos = (cursor_index.0 as usize, cursor_index.1 as usize);
+                        board.set(pos, solution.get(pos));
+                        if board != before.0 {
+                            solving = false;
+                            edited = true;
+                            stats.hints += 1;
+                        }
+                    },
+                    None => notice = Some(("No known solution".to_string(), Instant::now()))
+                },
+                Action::Check => match solution {
+                    Some(solution) => {
+                        wrong = (0..9).flat_map(|y| (0..9).map(move |x| (x, y)))
+                            .filter(|&pos| board.get(pos).digit().is_some_and(|digit| solution.get(pos).digit() != Some(digit)))
+                            .map(|(x, y)| (x as i8, y as i8))
+                            .collect();
+                        let text = match wrong.len() {
+                            0 => "No mistakes".to_string(),
+                            1 => "1 wrong digit".to_string(),
+                            count => format!("{count} wrong digits")
+                        };
+                        notice = Some((text, Instant::now()));
+                    },
+                    None => notice = Some(("No known solution".to_string(), Instant::now()))
+                },
+                Action::ToggleNote(num) => {
+                    for &pos in &picked {
+                        notes.toggle(pos, num);
+                    }
+                },
+                Action::CycleRegion => {
+                    region = match region {
+                        None => Some(Region::Row),
+                        Some(Region::Row) => Some(Region::Column),
+                        Some(Region::Column) => Some(Region::Box),
+                        Some(Region::Box) => None
+                    };
+                },
+                Action::FillRegion => {
+                    if let Some(region) = region {
+                        let unit = region.unit(cursor_index);
+                        let placed = fill_unit(&mut board, unit, solver_options.techniques);
+                        if placed.is_empty() {
+                            println!("Nothing more in {unit} follows from logic alone");
+                        }
+                        for step in &placed {
+                            println!("{step}");
+                        }
+                        solving = false;
+                        edited = true;
+                    }
+                },
+                Action::ToggleVisual => visual_solving = dbg!(!visual_solving),
+                Action::ToggleQr => show_qr = !show_qr,
+                Action::ToggleNoteMode => note_mode = !note_mode,
+                Action::ToggleDigitFirst => {
+                    digit_first = !digit_first;
+                    active_digit = None;
+                    picker = None;
+                },
+                Action::PickDigit(num) => active_digit = Some(num),
+                Action::CycleColor => {
+                    // Every picked cell takes the color after the cursor's
+                    let color = coloring.next((cursor_index.0 as usize, cursor_index.1 as usize));
+                    for &pos in &picked {
+                        coloring.set(pos, color);
+                    }
+                },
+                Action::ClearColors => coloring = Coloring::new(),
+                Action::ToggleEditMode => {
+                    editing = true;
+                    notice = Some(("Editing the givens".to_string(), Instant::now()));
+                },
+                Action::PauseTimer => timer.toggle_pause(),
+                Action::ReloadSettings => {
+                    // The size of the window and the font only change on the next launch
+                    config = load_config();
+                    bindings = key_bindings(&config);
+                    (theme, colors) = (config.theme, config.colors);
+                    println!("Reloaded the settings");
+                    notice = Some(("Reloaded the settings".to_string(), Instant::now()));
+                },
+                Action::Quit => running = false,
+                Action::ToggleMute => {
+                    let text = if sounds.toggle_mute() { "Sound off" } else { "Sound on" };
+                    notice = Some((text.to_string(), Instant::now()));
+                },
+                Action::Resize => {
+                    drawn_frame.drawn.forget();
+                    if ctx.fit_window() {
+                        match sys::load_font(&ttf, &config.font_path, ctx.tile_size) {
+                            Ok(resized) => {
+                                font = resized;
+                                glyphs.clear();
+                            },
+                            Err(err) => println!("Couldn't load the font at the new size: {err}")
+                        }
+                    }
+                },
+                Action::CycleTheme => {
+                    theme = theme.next();
+                    colors = theme.colors();
+                    println!("Theme: {theme:?}");
+                },
+                Action::FillNotes => notes = Notes::from_candidates(&Candidates::from_board(&board)),
+                Action::ClearBoard => match clear_requested.take().filter(|asked| asked.elapsed() < NOTICE_TIME) {
+                    Some(_) => {
+                        board = Board::new();
+                        sdk_file = None;
+                        loaded = true;
                         solving = false;
+                        edited = true;
+                    },
+                    None => {
+                        clear_requested = Some(Instant::now());
+                        notice = Some(("Press again to clear the board".to_string(), Instant::now()));
                     }
                 },
-                BoardState::Finished => {
+                Action::Restart => {
+                    // The givens stay, so the same puzzle starts over with the clock
+                    board.clear_soft();
+                    notes = Notes::new();
+                    coloring = Coloring::new();
+                    timer = Timer::start();
+                    stats = GameStats::new();
+                    show_stats = false;
                     solving = false;
+                    edited = true;
+                },
+                Action::ToggleCamera => {
+                    camera = match camera {
+                        Some(_) => None,
+                        None => Camera::open(&config.camera).map_err(|err| println!("Couldn't start ffmpeg for the camera: {err}")).ok()
+                    };
+                },
+                Action::Capture => {
+                    let read = camera.as_mut().and_then(Camera::poll).map(|frame| {
+                        Templates::render(&font).and_then(|templates| ocr::recognize(frame, &templates))
+                    });
+                    match read {
+                        Some(Ok(read)) => {
+                            println!("Read {} givens from the camera", read.clues());
+                            board = read;
+                            camera = None;
+                            sdk_file = None;
+                            loaded = true;
+                            solving = false;
+                            edited = true;
+                        },
+                        Some(Err(err)) => println!("Couldn't read the puzzle: {err}"),
+                        None => ()
+                    }
+                },
+                Action::Faster | Action::Slower => {
+                    speed = match action {
+                        Action::Faster => (speed + 1).min(SPEEDS.len() - 1),
+                        _ => speed.saturating_sub(1)
+                    };
+                    println!("Speed: {} steps per second", SPEEDS[speed]);
+                    notice = Some((format!("{} steps per second", SPEEDS[speed]), Instant::now()));
+                },
+                Action::ToggleOrder => {
+                    // Takes effect the next time the solver is reset
+                    solver_options.order = match solver_options.order {
+                        CellOrder::Raster => CellOrder::MostConstrained,
+                        CellOrder::MostConstrained => CellOrder::Raster
+                    };
+                    println!("Cell order: {}", solver_options.order);
+                },
+                Action::CycleStrategy => {
+                    // Takes effect the next time the solver is reset
+                    solver_options.strategy = solver_options.strategy.next();
+                    println!("Strategy: {}", solver_options.strategy);
+                },
+                Action::ToggleRandom => {
+                    // Clear the previous solution so the next solve starts over
+                    solver_options.seed = match solver_options.seed {
+                        Some(_) => None,
+                        None => Some(Rng::from_entropy().next_u64())
+                    };
+                    match solver_options.seed {
+                        Some(seed) => println!("Random order: on, seed {seed}"),
+                        None => println!("Random order: off")
+                    }
+                    board.clear_soft();
+                    solving = false;
+                    edited = true;
+                },
+                Action::Minimize => match minimize(&board) {
+                    Some(minimal) => {
+                        board = minimal;
+                        println!("{} clues left", board.clues());
+                        solving = false;
+                        edited = true;
+                    },
+                    None => println!("Only boards with a unique solution can be minimized")
+                },
+                Action::PrintBoard => { dbg!(&board); },
+                Action::Export => println!("{}", board.to_line()),
+                Action::NextPuzzle | Action::PreviousPuzzle | Action::OpenPuzzle(_) => {
+                    if let Some(collection) = &mut collection {
+                        board = match action {
+                            Action::NextPuzzle => collection.next(),
+                            Action::OpenPuzzle(index) => collection.select(index),
+                            _ => collection.previous()
+                        };
+                        sdk_file = None;
+                        loaded = true;
+                        solving = false;
+                        edited = true;
+                    }
+                },
+                Action::BrowsePuzzles => match &collection {
+                    Some(collection) => {
+                        let paused_timer = !timer.is_paused() && !timer.is_stopped();
+                        if paused_timer {
+                            timer.toggle_pause();
+                        }
+                        browser = Some(Browser { selected: collection.number() - 1, paused_timer, shelf: None });
+                    },
+                    None => notice = Some(("No collection opened".to_string(), Instant::now()))
+                },
+                Action::Save => {
+                    let (path, metadata) = sdk_file.clone().unwrap_or_else(|| (PathBuf::from(files::DEFAULT_SAVE_PATH), SdkMetadata::default()));
+                    match files::save_sdk(&path, &SdkPuzzle { board, metadata }) {
+                        Ok(()) => println!("Saved to {}", path.display()),
+                        Err(err) => println!("Couldn't save to {}: {err}", path.display())
+                    }
+                },
+                Action::SaveGame => {
+                    let path = Path::new(save::SAVE_PATH);
+                    match save::save(path, &SavedGame { board, cursor: cursor_index, coloring }) {
+                        Ok(()) => println!("Saved the game to {}", path.display()),
+                        Err(err) => println!("Couldn't save the game to {}: {err}", path.display())
+                    }
+                },
+                Action::LoadGame => {
+                    let path = Path::new(save::SAVE_PATH);
+                    match save::load(path) {
+                        Ok(game) => {
+                            board = game.board;
+                            cursor_index = (game.cursor.0.clamp(0, 8), game.cursor.1.clamp(0, 8));
+                            saved_coloring = Some(game.coloring);
+                            sdk_file = None;
+                            loaded = true;
+                            solving = false;
+                            edited = true;
+                        },
+                        Err(err) => println!("Couldn't load the game from {}: {err}", path.display())
+                    }
+                },
+                Action::ExportPng => {
+                    let path = Path::new(export::PNG_PATH);
+                    match export_png(&board, &mut ctx, &font, &mut glyphs, &mut drawn_frame, &colors, path) {
+                        Ok(()) => println!("Saved a picture of the board to {}", path.display()),
+                        Err(err) => println!("Couldn't save a picture to {}: {err}", path.display())
+                    }
+                },
+                Action::Copy => {
+                    if let Err(err) = clipboard.set_clipboard_text(&board.to_line()) {
+                        println!("Couldn't copy the board: {err}");
+                    }
+                },
+                Action::CopyCode => {
+                    let code = format!("{SHARE_PREFIX}{}", to_share_code(&board));
+                    match clipboard.set_clipboard_text(&code) {
+                        Ok(()) => println!("Copied {code}"),
+                        Err(err) => println!("Couldn't copy the share code: {err}")
+                    }
+                },
+                Action::Paste => match clipboard.clipboard_text().map(|text| files::parse_board(&text)) {
+                    Ok(Ok(pasted)) => {
+                        board = pasted;
+                        sdk_file = None;
+                        loaded = true;
+                        solving = false;
+                        edited = true;
+                    },
+                    Ok(Err(err)) => println!("Couldn't paste the board: {err}"),
+                    Err(err) => println!("Couldn't read the clipboard: {err}")
+                },
+                Action::Import => {
+                    // Blocks the window until a line has been entered in the terminal
+                    if let Some(imported) = read_board() {
+                        board = imported;
+                        sdk_file = None;
+                        loaded = true;
+                        solving = false;
+                        edited = true;
+                    }
+                },
+                Action::PrintLog => print!("{log}"),
+                Action::LoadTest => {
+                    board = test_board();
+                    sdk_file = None;
+                    loaded = true;
+                    edited = true;
+                },
+                Action::LoadDaily => {
+                    board = daily_puzzle(SystemTime::now());
+                    loaded_daily = Some(day_number(SystemTime::now()));
+                    sdk_file = None;
+                    loaded = true;
+                    edited = true;
+                },
+                Action::CycleDifficulty => {
+                    difficulty = match difficulty {
+                        Difficulty::Easy => Difficulty::Medium,
+                        Difficulty::Medium => Difficulty::Hard,
+                        Difficulty::Hard => Difficulty::Expert,
+                        Difficulty::Expert => Difficulty::Easy
+                    };
+                    println!("Difficulty: {difficulty}");
+                },
+                Action::Generate => {
+                    if let Some(generation) = &generation {
+                        generation.cancel();
+                    }
+                    generation = Some(Generation::start(difficulty));
+                    solving = false;
+                },
+                #[cfg(feature = "online")]
+                Action::LoadOnline => download = Some(online::Download::start(&config.online_url, difficulty)),
+                #[cfg(not(feature = "online"))]
+                Action::LoadOnline => println!("Downloading puzzles from {} needs a build with `--features online`", config.online_url),
+                Action::Cancel => {
+                    if let Some(generation) = generation.take() {
+                        generation.cancel();
+                    }
+                    #[cfg(feature = "online")]
+                    {
+                        download = None;
+                    }
+                    camera = None;
+                    picker = None;
+                    active_digit = None;
+                    show_stats = false;
+                },
+                Action::Click(..) | Action::RightClick(..) | Action::Count(_) | Action::ReplaceNext | Action::ToggleHelp | Action::Nothing => ()
+            }
+            picker.take_if(|cell| *cell != cursor_index);
+            if !matches!(action, Action::ClearBoard | Action::Nothing) {
+                clear_requested = None;
+            }
+            if !matches!(action, Action::Undo | Action::Redo) && (board, notes) != snapshot {
+                history.record(snapshot);
+            }
+            if (board, notes) != snapshot {
+                last_action = Some(describe(action, &picked));
+            }
+
+            if let Some(session) = &session {
+                let player = session.player;
+                match action {
+                    // Other players only know where this player's cursor is, so a
+                    // whole selection's edits go as the board
+                    Action::Write(_) | Action::Remove if picked.len() == 1 => {
+                        session.send(Message::Action { player, cursor: cursor_index, action, given: editing });
+                    },
+                    _ if board != before.0 && (!action.is_replayable() || picked.len() > 1) => session.send(Message::Board { player, board: Box::new(board) }),
+                    _ => ()
+                }
+                if cursor_index != before.1 {
+                    session.send(Message::Cursor { player, cursor: cursor_index });
+                }
+            }
+
+            if let Some((_, recorder)) = &mut recorder {
+                if action.is_replayable() {
+                    recorder.record(Event::Action(action));
+                } else {
+                    if solver_options.seed != before.2 {
+                        recorder.record(Event::Seed(solver_options.seed));
+                    }
+                    if (board, cursor_index) != (before.0, before.1) {
+                        recorder.record(Event::Board(Box::new(board), cursor_index));
+                    }
                 }
             }
         }
-        
-        let mut render = true;
-        match handle_input(&mut ctx.events, &mut running) {
-            Action::Move(x, y) => cursor_index = ((cursor_index.0 + x).clamp(0, 8), (cursor_index.1 + y).clamp(0, 8)),
-            Action::Solve => {
-                if valid_board(&board) {
-                    solving = !solving;
+
+        if loaded {
+            last_action = Some("New puzzle".to_string());
+            animations.clear();
+            show_stats = false;
+            notes = Notes::new();
+            coloring = saved_coloring.take().unwrap_or_default();
+            daily = loaded_daily.take();
+            browser = None;
+            solution = None;
+            editing = board.clues() == 0;
+            timer = Timer::start();
+            stats = GameStats::new();
+        }
+        if board.is_full() && board.is_valid() && !timer.is_stopped() {
+            timer.stop();
+            sounds.play(Effect::Solved);
+            println!("Solved in {}", timer::clock(timer.elapsed()));
+            // Boards that were loaded already solved have no game to show
+            if !loaded {
+                if !finished && let Some(collection) = &mut collection && collection.is_current(&board) {
+                    collection.mark_solved();
                 }
-            },
-            Action::Write(num) => board[cursor_index.1 as usize][cursor_index.0 as usize] = Tile::Hard(num),
-            Action::Remove => board[cursor_index.1 as usize][cursor_index.0 as usize] = Tile::Empty,
-            Action::ToggleVisual => visual_solving = dbg!(!visual_solving),
-            Action::PrintBoard => { dbg!(&board); },
-            Action::LoadTest => board = test_board(),
-            Action::Nothing => render = false
+                stats.finish(&board, finished.then_some(&progress));
+                add_record(&mut records, records_path.as_deref(), &stats, !finished, timer.elapsed(), daily);
+                toast = unlock(&mut achievements, achievements_path.as_deref(), &stats, !finished, timer.elapsed(), &records).or(toast);
+                show_stats = true;
+            }
+        }
+        // Undoing the last digit takes the stats away again
+        show_stats &= board.is_full();
+
+        if config.auto_eliminate_notes {
+            notes.eliminate_placed(&noted_board, &board);
+        }
+        noted_board = board;
+
+        if edited {
+            solver = solver_options.build();
+            last_step = None;
+            log.clear();
+            progress = Progress::new(&board);
+            // A solve can only be watched in full from the givens
+            stats.watched = board.clues() + board.empty_cells() == 81;
+            finished = false;
+            unsolvable = false;
+            solutions = count_solutions(&board, 2);
+            wrong.clear();
+        }
+        if solution.is_none() && solutions == 1 {
+            solution = find_solution(&board);
         }
         
         if visual_solving || !solving || render {
-            render_board(&board, cursor_index, &mut ctx, &font, solving);
+            let paused = !solving && !finished && log.position() > 0;
+            let banner = generation.as_ref().map(|generation| format!("Generating {}", generation.spinner()));
+            #[cfg(feature = "online")]
+            let banner = banner.or_else(|| download.as_ref().map(|download| format!("Downloading {}", download.spinner())));
+            let mut status = Vec::new();
+            if let Some(collection) = &collection {
+                match collection.label() {
+                    Some(label) => status.push(format!("puzzle {} of {} ({label})", collection.number(), collection.len())),
+                    None => status.push(format!("puzzle {} of {}", collection.number(), collection.len()))
+                }
+            }
+            match &generation {
+                Some(generation) => status.push(format!("generating {} puzzle, attempt {} (Esc to cancel)", generation.difficulty, generation.attempt)),
+                None if progress.steps > 0 => {
+                    status.push(format!("{} nodes, {} backtracks{}", progress.nodes, progress.backtracks, if paused { " (paused)" } else { "" }));
+                },
+                None => ()
+            }
+            #[cfg(feature = "online")]
+            if let Some(download) = &download {
+                status.push(format!("downloading {} puzzle (Esc to cancel)", download.difficulty));
+            }
+            let game_over = config.mistake_limit.is_some_and(|limit| stats.mistakes >= limit);
+            let time = timer::clock(timer.elapsed());
+            if game_over {
+                status.push(format!("game over at {time}"));
+            } else if timer.is_stopped() {
+                status.push(format!("solved in {time}"));
+            } else if timer.is_paused() {
+                status.push(format!("{time} (paused)"));
+            } else {
+                status.push(time);
+            }
+            match config.mistake_limit {
+                Some(limit) => status.push(format!("{} of {limit} mistakes", stats.mistakes)),
+                None if solution.is_some() => status.push(format!("{} mistakes", stats.mistakes)),
+                None => ()
+            }
+            if note_mode {
+                status.push("note mode".to_string());
+            }
+            match active_digit {
+                Some(digit) => status.push(format!("placing {digit}")),
+                None if digit_first => status.push("pick a digit".to_string()),
+                None => ()
+            }
+            if replacing {
+                status.push("r".to_string());
+            } else if count > 0 {
+                status.push(count.to_string());
+            }
+            if camera.is_some() {
+                status.push("camera on, Enter to read the puzzle, Esc to close".to_string());
+            }
+            let status = status.join(", ");
+            sys::set_title_status(&mut ctx, (!status.is_empty()).then_some(status.as_str()));
+            let background = match solutions {
+                _ if solving || paused => colors.background,
+                0 => colors.no_solution,
+                1 => colors.background,
+                _ => colors.several_solutions
+            };
+            let banner = match banner.as_ref().or(notice.as_ref().map(|(text, _)| text)) {
+                Some(text) => Some((text.as_str(), colors.banner)),
+                None if game_over => Some(("Game over", colors.error)),
+                None => unsolvable.then_some(("No solution", colors.error))
+            };
+            let qr = if show_qr { export::qr_code(&board).ok() } else { None };
+            let frame = camera.as_mut().and_then(Camera::poll).map(|frame| (frame, ocr::find_grid(frame)));
+            // The browser shows a page of the collection around the chosen puzzle
+            let (entries, browsed) = match browser.as_ref().and_then(|open| Some((open, open.browsed(&collection)?))) {
+                Some((open, collection)) => {
+                    let first = open.first(collection.len());
+                    let entries = (first..collection.len()).take(BROWSER_ROWS).map(|index| collection.entry(index)).collect();
+                    (entries, Some(open.selected - first))
+                },
+                _ => (Vec::new(), None)
+            };
+            let menu_labels: Vec<&str> = match &menu {
+                Some(menu) => menu.items.iter().map(|&(label, _)| label).collect(),
+                None => entries.iter().map(String::as_str).collect()
+            };
+            let overlay = match (show_records, show_stats) {
+                (true, _) => records.lines(day_number(SystemTime::now())),
+                (false, true) => stats.lines(timer.elapsed()),
+                (false, false) => Vec::new()
+            };
+            let help = match show_help {
+                true => {
+                    let mut modes = vec![if editing { "Edit mode: digits become givens" } else { "Play mode: digits become entries" }];
+                    if note_mode {
+                        modes.push("Note mode: digits are noted");
+                    }
+                    if digit_first {
+                        modes.push("Digit first: pick a digit, then click cells");
+                    }
+                    modes.push("Any key closes the help");
+                    help_lines(&bindings, &modes)
+                },
+                false => Vec::new()
+            };
+            let validity = match solutions {
+                _ if board.is_full() && board.is_valid() => "Solved",
+                0 if !board.is_valid() => "Breaks the rules",
+                0 => "No solution",
+                1 => "One solution",
+                _ => "Several solutions"
+            };
+            let mode = if solving && visual_solving {
+                format!("Solving at {}/s", SPEEDS[speed])
+            } else if solving {
+                "Solving".to_string()
+            } else if paused {
+                "Solver paused".to_string()
+            } else if editing {
+                "Editing".to_string()
+            } else if note_mode {
+                "Note mode".to_string()
+            } else {
+                "Playing".to_string()
+            };
+            let status_bar = [
+                format!("{} empty", board.empty_cells()),
+                validity.to_string(),
+                mode,
+                last_action.clone().unwrap_or_default()
+            ];
+            let view = View {
+                colors: &colors,
+                background,
+                cursor: Some(cursor_index),
+                region: region.map(|region| region.unit(cursor_index)),
+                progress: (solving || paused).then_some(&progress),
+                banner,
+                last_step: last_step.as_ref(),
+                players: players.iter().map(|(&player, &cursor)| (cursor, PLAYER_COLORS[player as usize % PLAYER_COLORS.len()])).collect(),
+                notes: Some(&notes),
+                coloring: Some(&coloring),
+                picker,
+                wrong: &wrong,
+                selection: &selection,
+                digit: active_digit,
+                panel: Some(Panel { note_mode, digit: active_digit, solving }),
+                menu: menu.as_ref().map(|menu| menu.selected).or(browsed).map(|selected| (&menu_labels[..], selected)),
+                status: &status_bar,
+                fades: &animations.current(),
+                overlay: &overlay,
+                toast: toast.as_ref().map(|(text, _)| text.as_str()),
+                help: &help,
+                qr: qr.as_ref(),
+                camera: frame
+            };
+            render_board(&board, &view, &mut ctx, &font, &mut glyphs, &mut drawn_frame);
         }
-    }
-    Ok(())
-}
 
-fn solve(board: &mut Board, solving_idx: usize) -> BoardState {
-    let pos = get_pos(solving_idx);
-    let prev = match board[pos.1][pos.0] {
-        Tile::Hard(_) => if solving_idx != 80 {
-            return BoardState::Solving(solving_idx + 1)
-        } else {
-            return BoardState::Finished
-        },
-        Tile::Soft(num) => num,
-        Tile::Empty => 0
-    };
-    
-    let mut possible: Vec<u8> = numbers().difference(&taken_values(board, pos)).map(|n| *n).collect();
-    possible.retain(|&n| n > prev);
-    possible.sort();
-    
-    match possible.first() {
-        Some(num) => {
-            board[pos.1][pos.0] = Tile::Soft(*num);
-            BoardState::Solving(solving_idx + 1)
-        },
-        None => {
-            if solving_idx != 0 {
-                board[pos.1][pos.0] = Tile::Empty;
-                BoardState::Solving(decrement_until_soft(solving_idx, board))
-            } else {
-                panic!("Trying to backtrack off the board");
+        if let Some(path) = &autosave_path {
+            let game = autosave_game(&board, cursor_index, &coloring);
+            let due = last_autosave.0.elapsed() >= save::AUTOSAVE_INTERVAL && game != last_autosave.1;
+            if due || !running {
+                if let Err(err) = save::save(path, &game) {
+                    println!("Couldn't autosave to {}: {err}", path.display());
+                }
+                last_autosave = (Instant::now(), game);
             }
         }
+
+        // Solving without visuals runs flat out, everything else waits for the next frame
+        if (!solving || visual_solving)
+            && let Some(rest) = sys::frame_time(config.fps).checked_sub(frame_start.elapsed())
+        {
+            std::thread::sleep(rest);
+        }
     }
-}
 
-fn decrement_until_soft(idx: usize, board: &Board) -> usize {
-    let mut idx = idx;
-    loop {
-        idx -= 1;
-        let pos = get_pos(idx);
-        if let Tile::Hard(_) = board[pos.1][pos.0] {
-            continue;
+    if let Some((path, recorder)) = &recorder {
+        match recorder.save(path) {
+            Ok(()) => println!("Saved the session to {}", path.display()),
+            Err(err) => println!("Couldn't save the session to {}: {err}", path.display())
         }
-        return idx;
     }
+    Ok(())
 }
 
-fn taken_values(board: &Board, pos: (usize, usize)) -> HashSet<u8> {
-    let mut numbers = HashSet::new();
-    
-    // Column
-    for y in 0..9 {
-        match board[y][pos.0] {
-            Tile::Soft(num) | Tile::Hard(num) => {
-                numbers.insert(num);
-            },
-            _ => ()
-        }
+/// The visual solving speeds to choose from with `+` and `-`, in steps per second.
+const SPEEDS: &[u32] = &[1, 2, 5, 10, 20, 60, 120, 300, 600, 1200, 3000, 6000, 12000];
+/// The index of the starting speed, about one step per frame.
+const DEFAULT_SPEED: usize = 5;
+/// The cursor colors of the players on a shared board, by player number.
+const PLAYER_COLORS: [Color; 6] = [
+    Color::RGB(220, 60, 60),
+    Color::RGB(40, 110, 230),
+    Color::RGB(0, 160, 80),
+    Color::RGB(230, 140, 0),
+    Color::RGB(150, 60, 200),
+    Color::RGB(0, 170, 190)
+];
+/// How long a notice stays on the board.
+const NOTICE_TIME: Duration = Duration::from_secs(2);
+/// How long the note of a newly unlocked achievement stays on the board.
+const TOAST_TIME: Duration = Duration::from_secs(3);
+/// How many steps to take between checks for input when solving without visuals.
+const UNTHROTTLED_STEPS: u32 = 4096;
+/// How many puzzles the browser shows at once.
+const BROWSER_ROWS: usize = 9;
+/// The items of the menu Escape opens, with what each one does.
+const MENU: [(&str, MenuItem); 7] = [
+    ("New game", MenuItem::NewGame),
+    ("Library", MenuItem::Library),
+    ("Load", MenuItem::Take(Action::LoadGame)),
+    ("Save", MenuItem::Take(Action::SaveGame)),
+    ("Stats", MenuItem::Stats),
+    ("Settings", MenuItem::Take(Action::ReloadSettings)),
+    ("Quit", MenuItem::Take(Action::Quit))
+];
+/// The menu's page for starting a new game, one item per difficulty.
+const NEW_GAME_MENU: [(&str, MenuItem); 5] = [
+    ("Easy", MenuItem::Generate(Difficulty::Easy)),
+    ("Medium", MenuItem::Generate(Difficulty::Medium)),
+    ("Hard", MenuItem::Generate(Difficulty::Hard)),
+    ("Expert", MenuItem::Generate(Difficulty::Expert)),
+    ("Back", MenuItem::Back)
+];
+/// The menu's page of the built-in library, one item per shelf.
+const LIBRARY_MENU: [(&str, MenuItem); 7] = [
+    (library::LIBRARY[0].0, MenuItem::Shelf(0)),
+    (library::LIBRARY[1].0, MenuItem::Shelf(1)),
+    (library::LIBRARY[2].0, MenuItem::Shelf(2)),
+    (library::LIBRARY[3].0, MenuItem::Shelf(3)),
+    (library::LIBRARY[4].0, MenuItem::Shelf(4)),
+    (library::LIBRARY[5].0, MenuItem::Shelf(5)),
+    ("Back", MenuItem::Back)
+];
+
+/// What choosing an item of the menu does.
+#[derive(Clone, Copy, PartialEq)]
+enum MenuItem {
+    /// Closes the menu and takes an action.
+    Take(Action),
+    /// Shows the difficulties to start a new game at.
+    NewGame,
+    /// Closes the menu and generates a puzzle of a difficulty.
+    Generate(Difficulty),
+    /// Goes back to the first page.
+    Back,
+    /// Closes the menu and shows the lifetime stats.
+    Stats,
+    /// Shows the shelves of the built-in library.
+    Library,
+    /// Opens a shelf of the library as a collection, in the browser.
+    Shelf(usize)
+}
+
+/// The menu over the board, which takes every key and click while it is open.
+struct Menu {
+    /// The page shown.
+    items: &'static [(&'static str, MenuItem)],
+    selected: usize,
+    /// Whether opening the menu paused the timer, to start it again on closing.
+    paused_timer: bool
+}
+
+/// Counts a game that just ended in the lifetime stats and saves them, if
+/// there is somewhere to.
+fn add_record(records: &mut Records, path: Option<&Path>, game: &GameStats, won: bool, time: Duration, daily: Option<u64>) {
+    records.add(game, won, time, daily);
+    if let Some(path) = path && let Err(err) = records.save(path) {
+        println!("Couldn't save the stats to {}: {err}", path.display());
     }
-    
-    // Row
-    for x in 0..9 {
-        match board[pos.1][x] {
-            Tile::Soft(num) | Tile::Hard(num) => {
-                numbers.insert(num);
-            },
-            _ => ()
-        }
+}
+
+/// Unlocks the achievements a game that just ended earns and saves them, if
+/// there is somewhere to, returning the toast telling of any new ones.
+fn unlock(achievements: &mut Achievements, path: Option<&Path>, game: &GameStats, won: bool, time: Duration, records: &Records) -> Option<(String, Instant)> {
+    let new = achievements.unlock(game, won, time, records);
+    for achievement in &new {
+        println!("Unlocked {}", achievement.name());
     }
-    
-    // Section
-    let top_left = ((pos.0 / 3) * 3, (pos.1 / 3) * 3);
-    for y in top_left.1..top_left.1 + 3 {
-        for x in top_left.0..top_left.0 + 3 {
-            match board[y][x] {
-                Tile::Soft(num) | Tile::Hard(num) => {
-                    numbers.insert(num);
-                },
-                _ => continue
-            }
-        }
+    let text = match new[..] {
+        [] => return None,
+        [achievement] => format!("Unlocked {}", achievement.name()),
+        _ => format!("{} new achievements", new.len())
+    };
+    if let Some(path) = path && let Err(err) = achievements.save(path) {
+        println!("Couldn't save the achievements to {}: {err}", path.display());
     }
-    
-    numbers
+    Some((text, Instant::now()))
 }
 
-fn get_pos(idx: usize) -> (usize, usize) {
-    (idx % 9, idx / 9)
+/// The list of the collection's puzzles, which takes every key and click while
+/// it is open.
+struct Browser {
+    /// The index of the chosen puzzle, counting from 0.
+    selected: usize,
+    /// Whether opening the browser paused the timer, to start it again on closing.
+    paused_timer: bool,
+    /// The library shelf listed instead of the opened collection, which
+    /// replaces it once one of its puzzles is opened.
+    shelf: Option<Collection>
 }
 
-enum BoardState {
-    // index of solving position
-    Solving(usize),
-    Finished
+impl Browser {
+    /// The puzzles listed: the shelf if there is one, or else `collection`.
+    fn browsed<'c>(&'c self, collection: &'c Option<Collection>) -> Option<&'c Collection> {
+        self.shelf.as_ref().or(collection.as_ref())
+    }
+
+    /// The index of the first puzzle shown out of `len`, keeping the chosen
+    /// one in the middle of the page where it can.
+    fn first(&self, len: usize) -> usize {
+        self.selected.saturating_sub(BROWSER_ROWS / 2).min(len.saturating_sub(BROWSER_ROWS))
+    }
 }
 
-fn render_board(board: &Board, cursor_index: (i8, i8), ctx: &mut SdlContext, font: &sdl2::ttf::Font, solving: bool) {
-    let bg_color = if solving || valid_board(board) {
-        Color::WHITE
-    } else {
-        Color::RGB(255, 220, 220)
-    };
-    ctx.canvas.set_draw_color(bg_color);
-    ctx.canvas.clear();
-    
-    draw_square(cursor_index, ctx, Color::RGB(200, 200, 200));
-    render_numbers(board, cursor_index, ctx, font);
-    
-    ctx.canvas.set_draw_color(Color::BLACK);
-    render_grid(ctx);
-    
-    ctx.canvas.present();
+/// The state to autosave, keeping only the givens. Digits placed by the
+/// solver are left out, since the solver itself can't be saved.
+fn autosave_game(board: &Board, cursor: (i8, i8), coloring: &Coloring) -> SavedGame {
+    let mut board = *board;
+    board.clear_soft();
+    SavedGame { board, cursor, coloring: *coloring }
+}
+
+/// The value following `flag` on the command line, if any.
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    let idx = args.iter().position(|arg| arg == flag)?;
+    args.get(idx + 1).map(String::as_str)
 }
 
-fn valid_board(board: &Board) -> bool {
-    for y in 0..3 {
-        for x in 0..3 {
-            if !valid_section((x, y), board) { return false }
+/// Flags that are followed by a value, which isn't a positional argument.
+const VALUE_FLAGS: [&str; 9] = ["--file", "--png", "--svg", "--latex", "--qr", "--record", "--replay", "--host", "--join"];
+
+/// The first argument that is neither a flag nor a flag's value.
+fn positional_arg(args: &[String]) -> Option<&str> {
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        if VALUE_FLAGS.contains(&arg.as_str()) {
+            rest.next();
+        } else if !arg.starts_with("--") {
+            return Some(arg);
         }
     }
-    
-    for y in 0..9 {
-        if !valid_row(y, board) { return false }
-    }
-    
-    for x in 0..9 {
-        if !valid_column(x, board) { return false }
+    None
+}
+
+/// A board read from a file, with where it came from.
+struct Opened {
+    board: Board,
+    /// The file and its metadata, for a `.sdk` file that Ctrl+S can save back to.
+    sdk_file: Option<(PathBuf, SdkMetadata)>,
+    /// The whole pack, if the file held several puzzles.
+    collection: Option<Collection>
+}
+
+/// Reads a puzzle or collection from `path`, or the givens from a picture.
+fn open_file(path: &Path, font: &sdl2::ttf::Font) -> Result<Opened, Box<dyn Error>> {
+    if ocr::is_image(path) {
+        return Ok(Opened { board: import_image(path, font)?, sdk_file: None, collection: None });
     }
-    
-    true
-}
-
-fn valid_column(x: usize, board: &Board) -> bool {
-    let mut numbers_hash: HashSet<u8> = HashSet::new();
-    let mut numbers_vec: Vec<u8> = Vec::with_capacity(9);
-    for y in 0..9 {
-        match board[y][x] {
-            Tile::Soft(num) | Tile::Hard(num) => {
-                numbers_vec.push(num);
-                numbers_hash.insert(num);
-            },
-            _ => continue
+    match files::load(path)? {
+        Loaded::Puzzle(puzzle) => {
+            let SdkPuzzle { board, metadata } = *puzzle;
+            let sdk_file = files::is_sdk(path).then(|| (path.to_path_buf(), metadata));
+            Ok(Opened { board, sdk_file, collection: None })
+        },
+        Loaded::Collection(puzzles) => {
+            let puzzles = Collection::new(puzzles).ok_or("the collection has no puzzles")?;
+            Ok(Opened { board: puzzles.current(), sdk_file: None, collection: Some(puzzles) })
         }
     }
-    numbers_vec.len() == numbers_hash.len()
 }
 
-fn valid_row(y: usize, board: &Board) -> bool {
-    let mut numbers_hash: HashSet<u8> = HashSet::new();
-    let mut numbers_vec: Vec<u8> = Vec::with_capacity(9);
-    for tile in board[y] {
-        match tile {
-            Tile::Soft(num) | Tile::Hard(num) => {
-                numbers_vec.push(num);
-                numbers_hash.insert(num);
-            },
-            _ => continue
-        }
+/// Reads the givens off a screenshot or photo of a puzzle.
+fn import_image(path: &Path, font: &sdl2::ttf::Font) -> Result<Board, Box<dyn Error>> {
+    let image = ocr::load_image(path)?;
+    let board = ocr::recognize(&image, &Templates::render(font)?)?;
+    println!("Read {} givens from {}", board.clues(), path.display());
+    if !board.is_valid() {
+        println!("Some digits were misread, since they break the rules; check the board against the picture");
     }
-    numbers_vec.len() == numbers_hash.len()
+    Ok(board)
 }
 
-fn valid_section(pos: (usize, usize), board: &Board) -> bool {
-    let top_left = (pos.0 * 3, pos.1 * 3);
-    let mut numbers_hash: HashSet<u8> = HashSet::new();
-    let mut numbers_vec: Vec<u8> = Vec::with_capacity(9);
-    for y in top_left.1..top_left.1 + 3 {
-        for x in top_left.0..top_left.0 + 3 {
-            match board[y][x] {
-                Tile::Soft(num) | Tile::Hard(num) => {
-                    numbers_vec.push(num);
-                    numbers_hash.insert(num);
-                },
-                _ => continue
-            }
+/// Asks for a board as one line of 81 characters or a share code on the terminal.
+fn read_board() -> Option<Board> {
+    print!("Enter a puzzle: ");
+    let _ = io::stdout().flush();
+    let mut line = String::new();
+    if io::stdin().read_line(&mut line).is_err() {
+        return None;
+    }
+    match files::parse_board(&line) {
+        Ok(board) => Some(board),
+        Err(err) => {
+            println!("Couldn't read the puzzle: {err}");
+            None
         }
     }
-    numbers_vec.len() == numbers_hash.len()
 }
 
-fn draw_square(pos: (i8, i8), ctx: &mut SdlContext, color: Color) {
-    ctx.canvas.set_draw_color(color);
-    let _ = ctx.canvas.fill_rect(Rect::new((pos.0 as u32 * TILE_SIZE) as _, (pos.1 as u32 * TILE_SIZE) as _, TILE_SIZE, TILE_SIZE));
+/// Takes one solver step and records it in `progress` and `log`.
+///
+/// If the log has been rewound, the next logged step is replayed instead, so
+/// the solver only continues once the board is back where it left off.
+fn advance(solver: &mut dyn Solver, board: &mut Board, progress: &mut Progress, log: &mut SolveLog) -> StepResult {
+    if let Some(step) = log.replay(board) {
+        progress.record(step);
+        return StepResult::Solving(step.clone());
+    }
+
+    let result = solver.step(board);
+    if let StepResult::Solving(step) = &result {
+        progress.record(step);
+        log.push(step.clone());
+    }
+    result
 }
 
-fn render_numbers(board: &Board, cursor_index: (i8, i8), ctx: &mut SdlContext, font: &sdl2::ttf::Font) {
-    for y in 0..board.len() {
-        for (x, tile) in board[y].iter().enumerate() {
-            match tile {
-                Tile::Hard(num) => {
-                    let color = if x == cursor_index.0 as _ && y == cursor_index.1 as _ {
-                        Color::RGB(200, 200, 0)
-                    } else {
-                        Color::YELLOW
-                    };
-                    draw_square((x as _, y as _), ctx, color);
-                    render_number(*num, (x as u32, y as u32), ctx, font);
-                },
-                Tile::Soft(num) => {
-                    render_number(*num, (x as u32, y as u32), ctx, font);
-                },
-                _ => ()
-            }
-        }
+/// Everything drawn besides the digits and the grid.
+struct View<'a> {
+    colors: &'a Colors,
+    background: Color,
+    cursor: Option<(i8, i8)>,
+    /// The row, column or box selected for filling.
+    region: Option<Unit>,
+    /// Shown as a bar along the bottom while a solve is underway.
+    progress: Option<&'a Progress>,
+    banner: Option<(&'a str, Color)>,
+    /// The solver's latest step, whose changed cells are tinted.
+    last_step: Option<&'a Step>,
+    /// The cursors of the other players on a shared board.
+    players: Vec<((i8, i8), Color)>,
+    notes: Option<&'a Notes>,
+    /// The colors cells were marked with.
+    coloring: Option<&'a Coloring>,
+    /// The cell the digit picker is open on.
+    picker: Option<(i8, i8)>,
+    /// Entries that don't match the solution.
+    wrong: &'a [(i8, i8)],
+    /// Cells picked along with the cursor.
+    selection: &'a [(i8, i8)],
+    /// The digit picked for placing.
+    digit: Option<u8>,
+    /// The buttons beside the board.
+    panel: Option<Panel>,
+    /// The items of the open menu, with the chosen one.
+    menu: Option<(&'a [&'a str], usize)>,
+    /// The pieces of the status bar under the board.
+    status: &'a [String],
+    /// Digits the solver is fading in or out.
+    fades: &'a [Fade],
+    /// Lines shown in a box over the board.
+    overlay: &'a [String],
+    /// A short note across the top of the board.
+    toast: Option<&'a str>,
+    /// The keys and modes, listed over the board and the panel.
+    help: &'a [String],
+    /// Drawn over everything else, for scanning the board with a phone.
+    qr: Option<&'a QrCode>,
+    /// The newest camera frame, with the corners of the grid found in it.
+    camera: Option<(&'a GrayImage, Option<Corners>)>
+}
+
+impl<'a> View<'a> {
+    /// Just the board, as for a picture of it.
+    fn clean(colors: &'a Colors) -> Self {
+        Self { colors, background: colors.background, cursor: None, region: None, progress: None, banner: None, last_step: None, players: Vec::new(), notes: None, coloring: None, picker: None, wrong: &[], selection: &[], digit: None, panel: None, menu: None, status: &[], fades: &[], overlay: &[], toast: None, help: &[], qr: None, camera: None }
     }
 }
 
-fn render_number(number: u8, pos: (u32, u32), ctx: &mut SdlContext, font: &sdl2::ttf::Font) {
-    let surface = font.render(&number.to_string()).blended(Color::BLACK).unwrap();
+/// Draws the board without the cursor or any highlights and saves it as a PNG.
+fn export_png(board: &Board, ctx: &mut SdlContext, font: &sdl2::ttf::Font, glyphs: &mut Glyphs, frame: &mut Frame, colors: &Colors, path: &Path) -> Result<(), Box<dyn Error>> {
+    draw_board(board, &View::clean(colors), ctx, font, glyphs, frame);
+    export::save_png(ctx, path)
+}
+
+fn render_board(board: &Board, view: &View, ctx: &mut SdlContext, font: &sdl2::ttf::Font, glyphs: &mut Glyphs, frame: &mut Frame) {
+    draw_board(board, view, ctx, font, glyphs, frame);
+    ctx.canvas.present();
+}
 
-    let texture = ctx
-        .texture_creator
-        .create_texture_from_surface(&surface)
-        .unwrap();
+/// Draws a frame without showing it, so it can also be read back for export.
+/// Only the cells that changed since `frame` was last drawn are drawn again.
+fn draw_board(board: &Board, view: &View, ctx: &mut SdlContext, font: &sdl2::ttf::Font, glyphs: &mut Glyphs, frame: &mut Frame) {
+    let palette = view.colors.palette();
+    let players: Vec<_> = view.players.iter().map(|&(pos, color)| (pos, config::to_rgb(color))).collect();
+    let scene = Scene {
+        palette: &palette,
+        background: config::to_rgb(view.background),
+        cursor: view.cursor,
+        region: view.region,
+        progress: view.progress,
+        banner: view.banner.map(|(text, color)| (text, config::to_rgb(color))),
+        last_step: view.last_step,
+        players: &players,
+        notes: view.notes,
+        coloring: view.coloring,
+        picker: view.picker,
+        wrong: view.wrong,
+        selection: view.selection,
+        digit: view.digit,
+        panel: view.panel,
+        menu: view.menu,
+        status: view.status,
+        fades: view.fades,
+        overlay: view.overlay,
+        toast: view.toast.map(|text| (text, config::to_rgb(view.colors.banner))),
+        help: view.help
+    };
+    let tile_size = ctx.tile_size;
+    frame.draw(&mut ctx.canvas, tile_size, |canvas, drawn| {
+        render::draw_changed(board, &scene, &mut SdlSurface { canvas, tile_size, font, glyphs }, drawn);
+    });
 
-    let sdl2::render::TextureQuery { width, height, .. } = texture.query();
+    if let Some(qr) = view.qr {
+        render_qr(qr, ctx);
+    }
 
-    let target = Rect::new((pos.0 * TILE_SIZE + TILE_SIZE / 2 - width / 2 + 1) as i32, (pos.1 * TILE_SIZE + TILE_SIZE / 2 - height / 2 + 2) as i32, width, height);
-    let _ = ctx.canvas.copy(&texture, None, Some(target));
+    if let Some((frame, grid)) = view.camera {
+        render_camera(frame, grid, view.colors.progress, ctx);
+    }
 }
 
-fn render_grid(ctx: &mut SdlContext) {
-    for x in 0..9 {
-        if x % 3 == 0 {
-            let _ = ctx.canvas.draw_line(Point::new((x * TILE_SIZE - 1) as _, 0), Point::new((x * TILE_SIZE - 1) as _, (LOGICAL_HEIGHT) as _));
-            let _ = ctx.canvas.draw_line(Point::new((x * TILE_SIZE + 1) as _, 0), Point::new((x * TILE_SIZE + 1) as _, (LOGICAL_HEIGHT) as _));
-        }
-        let _ = ctx.canvas.draw_line(Point::new((x * TILE_SIZE) as _, 0), Point::new((x * TILE_SIZE) as _, (LOGICAL_HEIGHT) as _));
+/// Draws a camera frame over the board, outlining the grid that would be read.
+fn render_camera(frame: &GrayImage, grid: Option<Corners>, outline: Color, ctx: &mut SdlContext) {
+    let (width, height) = (frame.width as u32, frame.height as u32);
+    let Ok(mut texture) = ctx.texture_creator.create_texture_streaming(PixelFormatEnum::RGB24, width, height) else {
+        return;
+    };
+    let rgb: Vec<u8> = frame.pixels.iter().flat_map(|&pixel| [pixel; 3]).collect();
+    let _ = texture.update(None, &rgb, frame.width * 3);
+
+    // Fit the frame to the width of the board, centered
+    let size = ctx.board_size();
+    let scale = size as f32 / width as f32;
+    let top = (size - (height as f32 * scale) as u32) / 2;
+    ctx.canvas.set_draw_color(Color::BLACK);
+    let _ = ctx.canvas.fill_rect(Rect::new(0, 0, size, size));
+    let _ = ctx.canvas.copy(&texture, None, Some(Rect::new(0, top as _, size, (height as f32 * scale) as u32)));
+
+    if let Some((min, max)) = grid {
+        ctx.canvas.set_draw_color(outline);
+        let corner = ((min.0 as f32 * scale) as i32, top as i32 + (min.1 as f32 * scale) as i32);
+        let _ = ctx.canvas.draw_rect(Rect::new(corner.0, corner.1, ((max.0 - min.0) as f32 * scale) as u32, ((max.1 - min.1) as f32 * scale) as u32));
     }
-    
-    for y in 0..9 {
-        if y % 3 == 0 {
-            let _ = ctx.canvas.draw_line(Point::new(0, (y * TILE_SIZE - 1) as _), Point::new((LOGICAL_WIDTH) as _, (y * TILE_SIZE - 1) as _));
-            let _ = ctx.canvas.draw_line(Point::new(0, (y * TILE_SIZE + 1) as _), Point::new((LOGICAL_WIDTH) as _, (y * TILE_SIZE + 1) as _));
+}
+
+/// Draws a QR code in black and white over the whole board, as large as whole
+/// pixels per module allow.
+fn render_qr(qr: &QrCode, ctx: &mut SdlContext) {
+    let size = ctx.board_size();
+    ctx.canvas.set_draw_color(Color::WHITE);
+    let _ = ctx.canvas.fill_rect(Rect::new(0, 0, size, size));
+
+    let modules = qr.width() as u32;
+    let module_size = (size / (modules + 2 * export::QR_QUIET_ZONE)).max(1);
+    let offset = (size - modules * module_size) / 2;
+    ctx.canvas.set_draw_color(Color::BLACK);
+    for (idx, color) in qr.to_colors().into_iter().enumerate() {
+        if color == qrcode::Color::Dark {
+            let (x, y) = (idx as u32 % modules, idx as u32 / modules);
+            let _ = ctx.canvas.fill_rect(Rect::new((offset + x * module_size) as _, (offset + y * module_size) as _, module_size, module_size));
         }
-        let _ = ctx.canvas.draw_line(Point::new(0, (y * TILE_SIZE) as _), Point::new((LOGICAL_WIDTH) as _, (y * TILE_SIZE) as _));
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Tile {
-    Hard(u8),
-    Soft(u8),
-    Empty
+/// The kind of unit the fill key works on, taken from around the cursor.
+#[derive(Clone, Copy)]
+enum Region {
+    Row,
+    Column,
+    Box
 }
 
+impl Region {
+    fn unit(self, cursor_index: (i8, i8)) -> Unit {
+        let pos = (cursor_index.0 as usize, cursor_index.1 as usize);
+        match self {
+            Region::Row => Unit::Row(pos.1),
+            Region::Column => Unit::Column(pos.0),
+            Region::Box => Unit::Box(box_index(pos))
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum Action {
     Write(u8),
     Remove,
     Move(i8, i8),
+    /// Puts the cursor on a cell, as by clicking it.
+    Select(i8, i8),
+    /// Moves the cursor by some cells, picking up both ends in the selection.
+    ExtendSelection(i8, i8),
+    /// Moves the cursor to a cell, as by dragging over it, picking up both
+    /// ends in the selection.
+    ExtendTo(i8, i8),
+    NextEmpty,
+    PreviousEmpty,
+    /// A left or right click at a point on the canvas, which depends on
+    /// whether the digit picker is open there.
+    Click(i32, i32),
+    RightClick(i32, i32),
+    /// Notes a digit in the cursor's cell, or takes the note out.
+    ToggleNote(u8),
+    /// Picks the digit that clicks place, when it is picked first.
+    PickDigit(u8),
+    /// A digit typed with vim keys, counting the next motion or, after `r`,
+    /// writing it.
+    Count(u8),
+    /// Writes the next digit typed with vim keys.
+    ReplaceNext,
     Solve,
+    StepOnce,
+    StepBack,
+    Faster,
+    Slower,
+    CycleRegion,
+    FillRegion,
+    Minimize,
+    CycleDifficulty,
+    Generate,
+    LoadOnline,
+    Cancel,
     ToggleVisual,
+    ToggleQr,
+    ToggleNoteMode,
+    ToggleDigitFirst,
+    /// Switches between writing givens and writing entries.
+    ToggleEditMode,
+    /// Shows the keys and modes over the board, or hides them.
+    ToggleHelp,
+    /// Marks the picked cells with the color after the cursor's, or none after the last.
+    CycleColor,
+    ClearColors,
+    PauseTimer,
+    CycleTheme,
+    /// Reads the settings file again, for the keys, colors and rules in it.
+    ReloadSettings,
+    Quit,
+    ToggleMute,
+    /// Empties every cell but the givens and takes out the notes, to try the puzzle again.
+    Restart,
+    /// Empties every cell, givens too, once pressed twice in a row.
+    ClearBoard,
+    /// Fits the board to the window's new size.
+    Resize,
+    Reveal,
+    Check,
+    Hint,
+    Undo,
+    Redo,
+    FillNotes,
+    ToggleCamera,
+    Capture,
+    ToggleOrder,
+    ToggleRandom,
+    CycleStrategy,
     PrintBoard,
+    Export,
+    Import,
+    NextPuzzle,
+    PreviousPuzzle,
+    /// Lists the opened collection's puzzles to pick one from.
+    BrowsePuzzles,
+    /// Moves to the collection's puzzle at an index, counting from 0, as
+    /// picked in the browser.
+    OpenPuzzle(usize),
+    Save,
+    SaveGame,
+    LoadGame,
+    ExportPng,
+    Copy,
+    CopyCode,
+    Paste,
+    PrintLog,
     LoadTest,
+    LoadDaily,
     Nothing
 }
 
+impl Action {
+    /// Whether doing this action again gives the same result as the first
+    /// time. The others depend on files, the clipboard, the date or chance,
+    /// or write files, so a recording keeps their result instead.
+    fn is_replayable(self) -> bool {
+        !matches!(
+            self,
+            Action::Import | Action::Paste | Action::LoadGame | Action::LoadDaily | Action::NextPuzzle | Action::PreviousPuzzle
+                | Action::BrowsePuzzles | Action::OpenPuzzle(_)
+                | Action::ToggleRandom | Action::Generate | Action::Cancel | Action::Save | Action::SaveGame | Action::ExportPng
+                | Action::Copy | Action::CopyCode | Action::ToggleCamera | Action::Capture | Action::LoadOnline
+                | Action::Undo | Action::Redo | Action::ReloadSettings | Action::Quit | Action::Resize | Action::ClearBoard
+        )
+    }
+
+    /// Looks up an action by the name it has in the `[keys]` table of the config file.
+    fn from_name(name: &str) -> Option<Self> {
+        // Digits are written by `write_1` to `write_9`
+        if let Some(num) = name.strip_prefix("write_").and_then(|num| num.parse().ok()).filter(|num| (1..=9).contains(num)) {
+            return Some(Action::Write(num));
+        }
+        ACTION_NAMES.iter().find(|&&(other, _)| other == name).map(|&(_, action)| action)
+    }
+}
+
+/// The actions that can be bound to keys in the config file, by name, besides
+/// `write_1` to `write_9`. Listed in the order the help shows them.
+const ACTION_NAMES: [(&str, Action); 65] = [
+    ("remove", Action::Remove),
+    ("left", Action::Move(-1, 0)),
+    ("right", Action::Move(1, 0)),
+    ("up", Action::Move(0, -1)),
+    ("down", Action::Move(0, 1)),
+    ("extend_left", Action::ExtendSelection(-1, 0)),
+    ("extend_right", Action::ExtendSelection(1, 0)),
+    ("extend_up", Action::ExtendSelection(0, -1)),
+    ("extend_down", Action::ExtendSelection(0, 1)),
+    ("next_empty", Action::NextEmpty),
+    ("previous_empty", Action::PreviousEmpty),
+    ("solve", Action::Solve),
+    ("step_once", Action::StepOnce),
+    ("step_back", Action::StepBack),
+    ("faster", Action::Faster),
+    ("slower", Action::Slower),
+    ("cycle_region", Action::CycleRegion),
+    ("fill_region", Action::FillRegion),
+    ("minimize", Action::Minimize),
+    ("cycle_difficulty", Action::CycleDifficulty),
+    ("generate", Action::Generate),
+    ("load_online", Action::LoadOnline),
+    ("cancel", Action::Cancel),
+    ("toggle_visual", Action::ToggleVisual),
+    ("toggle_qr", Action::ToggleQr),
+    ("toggle_note_mode", Action::ToggleNoteMode),
+    ("toggle_digit_first", Action::ToggleDigitFirst),
+    ("toggle_edit_mode", Action::ToggleEditMode),
+    ("cycle_color", Action::CycleColor),
+    ("clear_colors", Action::ClearColors),
+    ("pause_timer", Action::PauseTimer),
+    ("cycle_theme", Action::CycleTheme),
+    ("reload_settings", Action::ReloadSettings),
+    ("quit", Action::Quit),
+    ("toggle_help", Action::ToggleHelp),
+    ("toggle_mute", Action::ToggleMute),
+    ("restart", Action::Restart),
+    ("clear_board", Action::ClearBoard),
+    ("fill_notes", Action::FillNotes),
+    ("hint", Action::Hint),
+    ("reveal", Action::Reveal),
+    ("check", Action::Check),
+    ("undo", Action::Undo),
+    ("redo", Action::Redo),
+    ("toggle_camera", Action::ToggleCamera),
+    ("capture", Action::Capture),
+    ("toggle_order", Action::ToggleOrder),
+    ("toggle_random", Action::ToggleRandom),
+    ("cycle_strategy", Action::CycleStrategy),
+    ("print_board", Action::PrintBoard),
+    ("export", Action::Export),
+    ("import", Action::Import),
+    ("next_puzzle", Action::NextPuzzle),
+    ("previous_puzzle", Action::PreviousPuzzle),
+    ("browse_puzzles", Action::BrowsePuzzles),
+    ("save", Action::Save),
+    ("save_game", Action::SaveGame),
+    ("load_game", Action::LoadGame),
+    ("export_png", Action::ExportPng),
+    ("copy", Action::Copy),
+    ("copy_code", Action::CopyCode),
+    ("paste", Action::Paste),
+    ("print_log", Action::PrintLog),
+    ("load_test", Action::LoadTest),
+    ("load_daily", Action::LoadDaily)
+];
+
+/// The lines of the help: the modes that are on, then each action with the
+/// keys bound to it. Actions without keys are left out.
+fn help_lines(bindings: &HashMap<KeyCombo, Action>, modes: &[&str]) -> Vec<String> {
+    // Shortest first, so a digit is told by its key on the number row rather than the keypad
+    let keys = |action: Action| {
+        let mut keys: Vec<String> = bindings.iter().filter(|&(_, &bound)| bound == action).map(|(combo, _)| combo.name()).collect();
+        keys.sort_by_key(|key| (key.len(), key.clone()));
+        keys
+    };
+    let mut lines: Vec<String> = modes.iter().map(|mode| mode.to_string()).collect();
+    lines.push(String::new());
+    let digits: Vec<String> = (1..=9).filter_map(|num| keys(Action::Write(num)).into_iter().next()).collect();
+    if !digits.is_empty() {
+        lines.push(format!("{}: write", digits.join(" ")));
+    }
+    for (name, action) in ACTION_NAMES {
+        let keys = keys(action);
+        if !keys.is_empty() {
+            lines.push(format!("{}: {}", keys.join(", "), name.replace('_', " ")));
+        }
+    }
+    lines
+}
+
+/// What keeps the givens on `board` from starting a game, checked before edit
+/// mode is left, with the givens that clash with each other.
+fn givens_problem(board: &Board) -> Option<(&'static str, Vec<(i8, i8)>)> {
+    let mut givens = *board;
+    givens.clear_soft();
+    let conflicts: Vec<(i8, i8)> = givens.conflicts().into_iter().map(|(x, y)| (x as i8, y as i8)).collect();
+    if !conflicts.is_empty() {
+        Some(("The givens break the rules", conflicts))
+    } else if count_solutions(&givens, 1) == 0 {
+        Some(("The givens have no solution", Vec::new()))
+    } else {
+        None
+    }
+}
+
+/// What `action` did to the board or notes, for the status bar, with the
+/// cells it was taken on.
+fn describe(action: Action, picked: &[(usize, usize)]) -> String {
+    let cells = match picked {
+        [pos] => cell_name(*pos),
+        _ => format!("{} cells", picked.len())
+    };
+    match action {
+        Action::Write(num) => format!("Wrote {num} in {cells}"),
+        Action::Remove => format!("Erased {cells}"),
+        Action::ToggleNote(num) => format!("Toggled note {num} in {cells}"),
+        Action::FillNotes => "Filled in the notes".to_string(),
+        Action::Restart => "Restarted the puzzle".to_string(),
+        Action::Hint => "Took a hint".to_string(),
+        Action::Reveal => "Revealed a digit".to_string(),
+        Action::Undo => "Undid a change".to_string(),
+        Action::Redo => "Redid a change".to_string(),
+        Action::StepOnce | Action::StepBack => "Took a solver step".to_string(),
+        Action::FillRegion => "Filled the region".to_string(),
+        Action::Minimize => "Removed extra givens".to_string(),
+        _ => "Changed the board".to_string()
+    }
+}
+
+/// The settings in the config file, or the defaults if it can't be read.
+fn load_config() -> Config {
+    match config::config_path() {
+        Some(path) => config::load(&path).unwrap_or_else(|err| {
+            println!("Couldn't read the settings in {}, using the defaults: {err}", path.display());
+            Config::default()
+        }),
+        None => Config::default()
+    }
+}
+
+/// A key together with the modifiers held down with it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct KeyCombo {
+    key: Keycode,
+    ctrl: bool,
+    shift: bool
+}
+
+impl KeyCombo {
+    const fn plain(key: Keycode) -> Self {
+        Self { key, ctrl: false, shift: false }
+    }
+
+    const fn shift(key: Keycode) -> Self {
+        Self { key, ctrl: false, shift: true }
+    }
+
+    const fn ctrl(key: Keycode) -> Self {
+        Self { key, ctrl: true, shift: false }
+    }
+
+    const fn ctrl_shift(key: Keycode) -> Self {
+        Self { key, ctrl: true, shift: true }
+    }
+
+    /// The key as the settings write it, like `Ctrl+Shift+C`.
+    fn name(self) -> String {
+        format!("{}{}{}", if self.ctrl { "Ctrl+" } else { "" }, if self.shift { "Shift+" } else { "" }, self.key.name())
+    }
+
+    /// Reads a key as the settings write it, by SDL key name with any of
+    /// `Ctrl+` and `Shift+` in front, such as `Return` or `Ctrl+Shift+C`.
+    fn from_name(name: &str) -> Option<Self> {
+        let (mut ctrl, mut shift, mut rest) = (false, false, name);
+        loop {
+            if rest.len() > 5 && rest[..5].eq_ignore_ascii_case("ctrl+") {
+                (ctrl, rest) = (true, &rest[5..]);
+            } else if rest.len() > 6 && rest[..6].eq_ignore_ascii_case("shift+") {
+                (shift, rest) = (true, &rest[6..]);
+            } else {
+                break;
+            }
+        }
+        Keycode::from_name(rest).map(|key| Self { key, ctrl, shift })
+    }
+}
+
+/// The keys every action has unless the settings give it others.
+fn default_bindings() -> Vec<(KeyCombo, Action)> {
+    let digits = [
+        Keycode::NUM_1, Keycode::NUM_2, Keycode::NUM_3, Keycode::NUM_4, Keycode::NUM_5,
+        Keycode::NUM_6, Keycode::NUM_7, Keycode::NUM_8, Keycode::NUM_9
+    ];
+    let keypad = [
+        Keycode::KP_1, Keycode::KP_2, Keycode::KP_3, Keycode::KP_4, Keycode::KP_5,
+        Keycode::KP_6, Keycode::KP_7, Keycode::KP_8, Keycode::KP_9
+    ];
+    let mut bindings: Vec<_> = digits.into_iter().zip(1..)
+        .chain(keypad.into_iter().zip(1..))
+        .map(|(key, num)| (KeyCombo::plain(key), Action::Write(num)))
+        .collect();
+    bindings.extend([
+        (KeyCombo::plain(Keycode::Backspace), Action::Remove),
+        (KeyCombo::plain(Keycode::Right), Action::Move(1, 0)),
+        (KeyCombo::plain(Keycode::Left), Action::Move(-1, 0)),
+        (KeyCombo::plain(Keycode::Up), Action::Move(0, -1)),
+        (KeyCombo::plain(Keycode::Down), Action::Move(0, 1)),
+        (KeyCombo::shift(Keycode::Right), Action::ExtendSelection(1, 0)),
+        (KeyCombo::shift(Keycode::Left), Action::ExtendSelection(-1, 0)),
+        (KeyCombo::shift(Keycode::Up), Action::ExtendSelection(0, -1)),
+        (KeyCombo::shift(Keycode::Down), Action::ExtendSelection(0, 1)),
+        (KeyCombo::plain(Keycode::Tab), Action::NextEmpty),
+        (KeyCombo::shift(Keycode::Tab), Action::PreviousEmpty),
+        (KeyCombo::plain(Keycode::Space), Action::Solve),
+        (KeyCombo::plain(Keycode::KP_ENTER), Action::Solve),
+        (KeyCombo::plain(Keycode::N), Action::StepOnce),
+        (KeyCombo::plain(Keycode::B), Action::StepBack),
+        (KeyCombo::plain(Keycode::Plus), Action::Faster),
+        (KeyCombo::plain(Keycode::Equals), Action::Faster),
+        (KeyCombo::plain(Keycode::KpPlus), Action::Faster),
+        (KeyCombo::plain(Keycode::Minus), Action::Slower),
+        (KeyCombo::plain(Keycode::KpMinus), Action::Slower),
+        (KeyCombo::plain(Keycode::RightBracket), Action::Faster),
+        (KeyCombo::plain(Keycode::LeftBracket), Action::Slower),
+        (KeyCombo::plain(Keycode::G), Action::CycleRegion),
+        (KeyCombo::plain(Keycode::F), Action::FillRegion),
+        (KeyCombo::plain(Keycode::V), Action::ToggleVisual),
+        (KeyCombo::plain(Keycode::Q), Action::ToggleQr),
+        (KeyCombo::plain(Keycode::C), Action::ToggleNoteMode),
+        (KeyCombo::plain(Keycode::A), Action::FillNotes),
+        (KeyCombo::plain(Keycode::H), Action::Hint),
+        (KeyCombo::plain(Keycode::W), Action::ToggleCamera),
+        (KeyCombo::plain(Keycode::Return), Action::Capture),
+        (KeyCombo::plain(Keycode::O), Action::ToggleOrder),
+        (KeyCombo::plain(Keycode::R), Action::ToggleRandom),
+        (KeyCombo::plain(Keycode::S), Action::CycleStrategy),
+        (KeyCombo::plain(Keycode::T), Action::LoadTest),
+        (KeyCombo::plain(Keycode::D), Action::LoadDaily),
+        (KeyCombo::plain(Keycode::M), Action::Minimize),
+        (KeyCombo::plain(Keycode::Y), Action::CycleDifficulty),
+        (KeyCombo::plain(Keycode::F5), Action::SaveGame),
+        (KeyCombo::plain(Keycode::F6), Action::ReloadSettings),
+        (KeyCombo::plain(Keycode::F9), Action::LoadGame),
+        (KeyCombo::plain(Keycode::F12), Action::ExportPng),
+        (KeyCombo::plain(Keycode::PageDown), Action::NextPuzzle),
+        (KeyCombo::plain(Keycode::PageUp), Action::PreviousPuzzle),
+        (KeyCombo::ctrl(Keycode::B), Action::BrowsePuzzles),
+        (KeyCombo::plain(Keycode::X), Action::Generate),
+        (KeyCombo::plain(Keycode::U), Action::LoadOnline),
+        (KeyCombo::plain(Keycode::Escape), Action::Cancel),
+        (KeyCombo::plain(Keycode::P), Action::PrintBoard),
+        (KeyCombo::plain(Keycode::E), Action::Export),
+        (KeyCombo::plain(Keycode::I), Action::Import),
+        (KeyCombo::plain(Keycode::L), Action::PrintLog),
+        (KeyCombo::ctrl(Keycode::S), Action::Save),
+        (KeyCombo::ctrl_shift(Keycode::C), Action::CopyCode),
+        (KeyCombo::ctrl(Keycode::C), Action::Copy),
+        (KeyCombo::ctrl(Keycode::V), Action::Paste),
+        (KeyCombo::ctrl_shift(Keycode::Z), Action::Redo),
+        (KeyCombo::ctrl(Keycode::Z), Action::Undo),
+        (KeyCombo::ctrl(Keycode::Y), Action::Redo),
+        (KeyCombo::ctrl(Keycode::R), Action::Reveal),
+        (KeyCombo::ctrl(Keycode::K), Action::Check),
+        (KeyCombo::ctrl(Keycode::P), Action::PauseTimer),
+        (KeyCombo::ctrl(Keycode::T), Action::CycleTheme),
+        (KeyCombo::ctrl(Keycode::D), Action::ToggleDigitFirst),
+        (KeyCombo::ctrl(Keycode::E), Action::ToggleEditMode),
+        (KeyCombo::plain(Keycode::F1), Action::ToggleHelp),
+        (KeyCombo::plain(Keycode::Z), Action::CycleColor),
+        (KeyCombo::shift(Keycode::Z), Action::ClearColors),
+        (KeyCombo::shift(Keycode::Slash), Action::ToggleHelp),
+        (KeyCombo::ctrl(Keycode::M), Action::ToggleMute),
+        (KeyCombo::ctrl(Keycode::N), Action::Restart),
+        (KeyCombo::ctrl(Keycode::Backspace), Action::ClearBoard)
+    ]);
+    bindings
+}
+
+/// Keys that move like in vim, taking the place of the default ones. Digits
+/// count the next motion, as in `3l`, and are written after `r`.
+fn vim_bindings() -> Vec<(KeyCombo, Action)> {
+    let digits = [
+        Keycode::NUM_0, Keycode::NUM_1, Keycode::NUM_2, Keycode::NUM_3, Keycode::NUM_4,
+        Keycode::NUM_5, Keycode::NUM_6, Keycode::NUM_7, Keycode::NUM_8, Keycode::NUM_9
+    ];
+    let mut bindings: Vec<_> = digits.into_iter().zip(0..).map(|(key, num)| (KeyCombo::plain(key), Action::Count(num))).collect();
+    bindings.extend([
+        (KeyCombo::plain(Keycode::H), Action::Move(-1, 0)),
+        (KeyCombo::plain(Keycode::J), Action::Move(0, 1)),
+        (KeyCombo::plain(Keycode::K), Action::Move(0, -1)),
+        (KeyCombo::plain(Keycode::L), Action::Move(1, 0)),
+        (KeyCombo::plain(Keycode::X), Action::Remove),
+        (KeyCombo::plain(Keycode::R), Action::ReplaceNext)
+    ]);
+    bindings
+}
+
+/// The action of every key: the defaults, except that actions named in the
+/// settings have the keys given there instead. Entries that don't name an
+/// action or a key are skipped.
+fn key_bindings(config: &Config) -> HashMap<KeyCombo, Action> {
+    let mut configured = Vec::new();
+    for (name, keys) in &config.keys {
+        let Some(action) = Action::from_name(name) else {
+            println!("Unknown action {name} in the settings");
+            continue;
+        };
+        configured.push((action, None));
+        for key in keys.names() {
+            match KeyCombo::from_name(key) {
+                Some(combo) => configured.push((action, Some(combo))),
+                None => println!("Unknown key {key:?} for {name} in the settings")
+            }
+        }
+    }
+
+    let mut bindings: HashMap<_, _> = default_bindings()
+        .into_iter()
+        .filter(|(_, action)| !configured.iter().any(|(other, _)| other == action))
+        .collect();
+    if config.vim_keys {
+        bindings.extend(vim_bindings());
+    }
+    bindings.extend(configured.into_iter().filter_map(|(action, combo)| Some((combo?, action))));
+    bindings
+}
+
+/// Turns every event since the last frame into an action, leaving out the ones
+/// that do nothing. Files dropped on the window are added to `dropped`.
 fn handle_input(
     events: &mut EventPump,
     running: &mut bool,
+    dropped: &mut Vec<PathBuf>,
+    bindings: &HashMap<KeyCombo, Action>,
+    tile_size: u32,
+) -> Vec<Action> {
+    events
+        .poll_iter()
+        .map(|event| handle_event(event, running, dropped, bindings, tile_size))
+        .filter(|action| !matches!(action, Action::Nothing))
+        .collect()
+}
+
+/// The cell under a point on the canvas, if it is on the board. SDL gives
+/// mouse positions on the canvas already, undoing the window's scaling.
+fn cell_at(x: i32, y: i32, tile_size: u32) -> Option<(i8, i8)> {
+    let size = tile_size as i32 * 9;
+    ((0..size).contains(&x) && (0..size).contains(&y)).then(|| ((x / tile_size as i32) as i8, (y / tile_size as i32) as i8))
+}
+
+fn handle_event(
+    event: sdl2::event::Event,
+    running: &mut bool,
+    dropped: &mut Vec<PathBuf>,
+    bindings: &HashMap<KeyCombo, Action>,
+    tile_size: u32
 ) -> Action {
-    if let Some(event) = events.poll_iter().next() {
-        use sdl2::event::Event as Ev;
-
-        return match event {
-            Ev::Quit { .. } => {
-                *running = false;
-                Action::Nothing
-            },
-            Ev::KeyDown {
-                keycode: Some(kc),
-                repeat: false,
-                ..
-            } => match kc {
-                Keycode::Num1 => Action::Write(1),
-                Keycode::NUM_2 => Action::Write(2),
-                Keycode::NUM_3 => Action::Write(3),
-                Keycode::NUM_4 => Action::Write(4),
-                Keycode::NUM_5 => Action::Write(5),
-                Keycode::NUM_6 => Action::Write(6),
-                Keycode::NUM_7 => Action::Write(7),
-                Keycode::NUM_8 => Action::Write(8),
-                Keycode::NUM_9 => Action::Write(9),
-                Keycode::Backspace => Action::Remove,
-                Keycode::Right => Action::Move(1, 0),
-                Keycode::Left => Action::Move(-1, 0),
-                Keycode::Up => Action::Move(0, -1),
-                Keycode::Down => Action::Move(0, 1),
-                Keycode::Space => Action::Solve,
-                Keycode::V => Action::ToggleVisual,
-                Keycode::T => Action::LoadTest,
-                Keycode::P => Action::PrintBoard,
-                _ => Action::Nothing,
-            },
-            Ev::KeyDown {
-                keycode: Some(kc),
-                repeat: true,
-                ..
-            } => match kc {
-                Keycode::Right => Action::Move(1, 0),
-                Keycode::Left => Action::Move(-1, 0),
-                Keycode::Up => Action::Move(0, -1),
-                Keycode::Down => Action::Move(0, 1),
-                _ => Action::Nothing,
-            },
-            _ => Action::Nothing,
-        }
+    use sdl2::event::Event as Ev;
+
+    match event {
+        Ev::Quit { .. } => {
+            *running = false;
+            Action::Nothing
+        },
+        Ev::MouseButtonDown { mouse_btn: MouseButton::Left, x, y, .. } => Action::Click(x, y),
+        Ev::MouseButtonDown { mouse_btn: MouseButton::Right, x, y, .. } => Action::RightClick(x, y),
+        // Dragging with the button held selects the cells passed over, once per cell
+        Ev::MouseMotion { mousestate, x, y, xrel, yrel, .. } if mousestate.left() => match cell_at(x, y, tile_size) {
+            Some(cell) if cell_at(x - xrel, y - yrel, tile_size) != Some(cell) => Action::ExtendTo(cell.0, cell.1),
+            _ => Action::Nothing
+        },
+        // Sent for every change of size, whether by the user or the system
+        Ev::Window { win_event: WindowEvent::SizeChanged(..), .. } => Action::Resize,
+        // Some drivers lose what was drawn on textures, which then needs drawing in full
+        Ev::RenderTargetsReset { .. } | Ev::RenderDeviceReset { .. } => Action::Resize,
+        Ev::DropFile { filename, .. } => {
+            dropped.push(PathBuf::from(filename));
+            Action::Nothing
+        },
+        Ev::KeyDown { keycode: Some(key), keymod, repeat, .. } => {
+            let ctrl = keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+            let shift = keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+            // Keys bound without shift work with it held too, as for `+` where it takes shift to type
+            let action = bindings
+                .get(&KeyCombo { key, ctrl, shift })
+                .or_else(|| bindings.get(&KeyCombo { key, ctrl, shift: false }))
+                .copied()
+                .unwrap_or(Action::Nothing);
+            // Holding a key down only keeps moving the cursor
+            match action {
+                Action::Move(..) | Action::ExtendSelection(..) => action,
+                _ if repeat => Action::Nothing,
+                _ => action
+            }
+        },
+        _ => Action::Nothing,
     }
-    Action::Nothing
 }
@@ -0,0 +1,270 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::BufReader,
+    path::Path
+};
+
+use sdl2::{pixels::{Color, PixelFormatEnum}, ttf::Font};
+use sudoku_core::{get_pos, Board, Tile};
+
+/// A picture reduced to brightness, from 0 for black to 255 for white.
+pub struct GrayImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>
+}
+
+/// Returns true if `path` is a picture that [`load_image`] can read.
+pub fn is_image(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| {
+        ["png", "jpg", "jpeg"].iter().any(|image| ext.eq_ignore_ascii_case(image))
+    })
+}
+
+/// Reads a PNG or JPEG file, dropping the colors and putting transparent
+/// parts on white.
+pub fn load_image(path: &Path) -> Result<GrayImage, Box<dyn Error>> {
+    let is_png = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+    if is_png {
+        let mut decoder = png::Decoder::new(File::open(path)?);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        let channels = info.color_type.samples();
+        let pixels = buffer[..info.buffer_size()].chunks(channels).map(|pixel| match *pixel {
+            [gray] => gray,
+            [gray, alpha] => over_white(gray, alpha),
+            [r, g, b] => luminance(r, g, b),
+            [r, g, b, alpha] => over_white(luminance(r, g, b), alpha),
+            _ => unreachable!("PNG pixels have one to four samples")
+        }).collect();
+        Ok(GrayImage { width: info.width as usize, height: info.height as usize, pixels })
+    } else {
+        let mut decoder = jpeg_decoder::Decoder::new(BufReader::new(File::open(path)?));
+        let buffer = decoder.decode()?;
+        let info = decoder.info().ok_or("the JPEG file has no image")?;
+        let pixels = match info.pixel_format {
+            jpeg_decoder::PixelFormat::L8 => buffer,
+            jpeg_decoder::PixelFormat::L16 => buffer.chunks(2).map(|pixel| pixel[0]).collect(),
+            jpeg_decoder::PixelFormat::RGB24 => buffer.chunks(3).map(|pixel| luminance(pixel[0], pixel[1], pixel[2])).collect(),
+            jpeg_decoder::PixelFormat::CMYK32 => buffer.chunks(4).map(|pixel| 255 - pixel[3]).collect()
+        };
+        Ok(GrayImage { width: info.width as usize, height: info.height as usize, pixels })
+    }
+}
+
+fn luminance(r: u8, g: u8, b: u8) -> u8 {
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+fn over_white(gray: u8, alpha: u8) -> u8 {
+    ((gray as u32 * alpha as u32 + 255 * (255 - alpha as u32)) / 255) as u8
+}
+
+/// The brightness that best splits the pixels into ink and paper, by Otsu's method.
+fn threshold(pixels: &[u8]) -> u8 {
+    let mut histogram = [0u64; 256];
+    for &pixel in pixels {
+        histogram[pixel as usize] += 1;
+    }
+    let total = pixels.len() as f64;
+    let sum: f64 = histogram.iter().enumerate().map(|(value, &count)| value as f64 * count as f64).sum();
+
+    let (mut best, mut best_variance) = (127, 0.0);
+    let (mut dark_count, mut dark_sum) = (0.0, 0.0);
+    for (value, &count) in histogram.iter().enumerate() {
+        dark_count += count as f64;
+        dark_sum += value as f64 * count as f64;
+        if dark_count == 0.0 || dark_count == total {
+            continue;
+        }
+        let dark_mean = dark_sum / dark_count;
+        let light_mean = (sum - dark_sum) / (total - dark_count);
+        let variance = dark_count * (total - dark_count) * (dark_mean - light_mean).powi(2);
+        if variance > best_variance {
+            (best, best_variance) = (value as u8, variance);
+        }
+    }
+    best
+}
+
+/// Connected dark pixels, with their bounding box.
+struct Component {
+    pixels: Vec<(usize, usize)>,
+    min: (usize, usize),
+    max: (usize, usize)
+}
+
+impl Component {
+    fn width(&self) -> usize {
+        self.max.0 - self.min.0 + 1
+    }
+
+    fn height(&self) -> usize {
+        self.max.1 - self.min.1 + 1
+    }
+}
+
+/// Finds the groups of touching dark pixels in the part of `dark` between
+/// `from` and `to`, exclusive.
+fn components(dark: &[bool], width: usize, from: (usize, usize), to: (usize, usize)) -> Vec<Component> {
+    let region_width = to.0 - from.0;
+    let mut seen = vec![false; region_width * (to.1 - from.1)];
+    let mut found = Vec::new();
+    for y in from.1..to.1 {
+        for x in from.0..to.0 {
+            if !dark[y * width + x] || seen[(y - from.1) * region_width + x - from.0] {
+                continue;
+            }
+
+            seen[(y - from.1) * region_width + x - from.0] = true;
+            let mut component = Component { pixels: Vec::new(), min: (x, y), max: (x, y) };
+            let mut stack = vec![(x, y)];
+            while let Some((x, y)) = stack.pop() {
+                component.pixels.push((x, y));
+                component.min = (component.min.0.min(x), component.min.1.min(y));
+                component.max = (component.max.0.max(x), component.max.1.max(y));
+                // Diagonal neighbours count too, so thin slanted strokes hold together
+                let neighbours = (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (x.wrapping_add_signed(dx), y.wrapping_add_signed(dy))));
+                for (nx, ny) in neighbours {
+                    if (from.0..to.0).contains(&nx) && (from.1..to.1).contains(&ny) {
+                        let idx = (ny - from.1) * region_width + nx - from.0;
+                        if dark[ny * width + nx] && !seen[idx] {
+                            seen[idx] = true;
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+            }
+            found.push(component);
+        }
+    }
+    found
+}
+
+/// The side of the square a digit is scaled into before it is compared.
+const GLYPH_SIZE: usize = 16;
+
+/// A digit centered in a square and scaled down, as the share of ink in each sample.
+struct Glyph([f32; GLYPH_SIZE * GLYPH_SIZE]);
+
+impl Glyph {
+    fn new(component: &Component) -> Self {
+        // Keeping the aspect ratio tells a narrow 1 from a wide 7
+        let side = component.width().max(component.height());
+        let offset = ((side - component.width()) / 2, (side - component.height()) / 2);
+        let mut ink = [0.0; GLYPH_SIZE * GLYPH_SIZE];
+        let mut area = [0.0; GLYPH_SIZE * GLYPH_SIZE];
+        for &(x, y) in &component.pixels {
+            let (x, y) = (x - component.min.0 + offset.0, y - component.min.1 + offset.1);
+            ink[y * GLYPH_SIZE / side * GLYPH_SIZE + x * GLYPH_SIZE / side] += 1.0;
+        }
+        for y in 0..side {
+            for x in 0..side {
+                area[y * GLYPH_SIZE / side * GLYPH_SIZE + x * GLYPH_SIZE / side] += 1.0;
+            }
+        }
+        Glyph(std::array::from_fn(|idx| if area[idx] > 0.0 { ink[idx] / area[idx] } else { 0.0 }))
+    }
+
+    fn distance(&self, other: &Glyph) -> f32 {
+        self.0.iter().zip(&other.0).map(|(a, b)| (a - b).powi(2)).sum()
+    }
+}
+
+/// The digits 1 to 9 to compare the cells of a picture with.
+pub struct Templates(Vec<(u8, Glyph)>);
+
+impl Templates {
+    /// Draws the digits in `font`, which works best when it looks like the
+    /// font of the puzzles being read.
+    pub fn render(font: &Font) -> Result<Self, Box<dyn Error>> {
+        let mut glyphs = Vec::new();
+        for digit in 1..=9u8 {
+            let surface = font.render(&digit.to_string()).blended(Color::BLACK)?.convert_format(PixelFormatEnum::RGBA32)?;
+            let (width, height, pitch) = (surface.width() as usize, surface.height() as usize, surface.pitch() as usize);
+            let pixels = surface.without_lock().ok_or("the digit surface can't be read")?;
+            let dark: Vec<bool> = (0..width * height).map(|idx| pixels[idx / width * pitch + idx % width * 4 + 3] > 127).collect();
+            let glyph = components(&dark, width, (0, 0), (width, height))
+                .into_iter()
+                .max_by_key(|component| component.pixels.len())
+                .ok_or("the font draws digits as nothing")?;
+            glyphs.push((digit, Glyph::new(&glyph)));
+        }
+        Ok(Self(glyphs))
+    }
+
+    fn closest(&self, glyph: &Glyph) -> u8 {
+        self.0.iter()
+            .min_by(|(_, a), (_, b)| a.distance(glyph).total_cmp(&b.distance(glyph)))
+            .map_or(1, |&(digit, _)| digit)
+    }
+}
+
+/// Which pixels are ink rather than paper.
+fn ink(image: &GrayImage) -> Vec<bool> {
+    let level = threshold(&image.pixels);
+    image.pixels.iter().map(|&pixel| pixel <= level).collect()
+}
+
+/// The largest connected set of ink, if it is about square and big enough to
+/// be a grid.
+fn grid(dark: &[bool], image: &GrayImage) -> Option<Component> {
+    let grid = components(dark, image.width, (0, 0), (image.width, image.height))
+        .into_iter()
+        .max_by_key(|component| component.width() * component.height())?;
+    let aspect = grid.width() as f32 / grid.height() as f32;
+    (grid.width() >= 9 * 8 && (0.8..1.25).contains(&aspect)).then_some(grid)
+}
+
+/// The top left and bottom right corners of a rectangle in an image.
+pub type Corners = ((usize, usize), (usize, usize));
+
+/// The corners of the grid in `image`, if there is one, for showing what
+/// [`recognize`] would read.
+pub fn find_grid(image: &GrayImage) -> Option<Corners> {
+    grid(&ink(image), image).map(|grid| (grid.min, grid.max))
+}
+
+/// How much of each side of a cell is left out, so grid lines aren't taken for ink.
+const CELL_MARGIN: f32 = 0.12;
+
+/// Reads the printed digits of a grid in `image` as givens.
+///
+/// The grid is taken to be the largest connected set of dark pixels, which
+/// works for screenshots and straight scans; photos need to be cropped and
+/// straightened first. Each cell is then split off, and its largest blot of
+/// ink, if big enough to be a digit, is matched against `templates`.
+pub fn recognize(image: &GrayImage, templates: &Templates) -> Result<Board, Box<dyn Error>> {
+    let dark = ink(image);
+    let grid = grid(&dark, image).ok_or("couldn't find a grid in the picture")?;
+
+    let cell = (grid.width() as f32 / 9.0, grid.height() as f32 / 9.0);
+    let mut board = Board::new();
+    for idx in 0..81 {
+        let pos = get_pos(idx);
+        let left = grid.min.0 as f32 + pos.0 as f32 * cell.0;
+        let top = grid.min.1 as f32 + pos.1 as f32 * cell.1;
+        let from = ((left + cell.0 * CELL_MARGIN) as usize, (top + cell.1 * CELL_MARGIN) as usize);
+        let to = ((left + cell.0 * (1.0 - CELL_MARGIN)) as usize, (top + cell.1 * (1.0 - CELL_MARGIN)) as usize);
+
+        // Bits of grid line the margin didn't cut off lie thinly along its edge
+        let ink = components(&dark, image.width, from, to)
+            .into_iter()
+            .filter(|component| {
+                let thin = (component.width() as f32) < cell.0 * 0.2 || (component.height() as f32) < cell.1 * 0.2;
+                let at_edge = component.min.0 == from.0 || component.min.1 == from.1 || component.max.0 == to.0 - 1 || component.max.1 == to.1 - 1;
+                !(thin && at_edge)
+            })
+            .max_by_key(|component| component.pixels.len());
+        if let Some(ink) = ink
+            && ink.height() as f32 >= cell.1 * 0.25
+            && ink.pixels.len() as f32 >= cell.0 * cell.1 * 0.015
+        {
+            board.set(pos, Tile::Hard(templates.closest(&Glyph::new(&ink))));
+        }
+    }
+    Ok(board)
+}
@@ -0,0 +1,153 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant}
+};
+
+use serde_json::Value;
+use sudoku_core::{parse_sdm, rate, Board, Difficulty};
+
+/// How many puzzles are downloaded looking for one of the chosen difficulty
+/// before giving up. The others are cached for later.
+const MAX_DOWNLOADS: usize = 5;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The puzzles of `difficulty` downloaded but not played yet, one per line,
+/// in the platform's data directory for the app. Returns `None` if SDL can't
+/// find or create it.
+fn cache_path(difficulty: Difficulty) -> Option<PathBuf> {
+    let dir = sdl2::filesystem::pref_path("GardSvard", "sudoku-solver").ok()?;
+    Some(Path::new(&dir).join(format!("online-{difficulty}.sdm")))
+}
+
+/// Takes the oldest puzzle of `difficulty` out of the cache.
+fn take_cached(difficulty: Difficulty) -> Option<Board> {
+    let path = cache_path(difficulty)?;
+    let mut puzzles = parse_sdm(&fs::read_to_string(&path).ok()?).ok()?;
+    if puzzles.is_empty() {
+        return None;
+    }
+    let puzzle = puzzles.remove(0);
+    let rest: String = puzzles.iter().map(|board| board.to_line() + "\n").collect();
+    fs::write(&path, rest).ok()?;
+    Some(puzzle)
+}
+
+fn cache(board: &Board, difficulty: Difficulty) {
+    let Some(path) = cache_path(difficulty) else {
+        return;
+    };
+    let mut text = fs::read_to_string(&path).unwrap_or_default();
+    text.push_str(&board.to_line());
+    text.push('\n');
+    if let Err(err) = fs::write(&path, text) {
+        println!("Couldn't cache the downloaded puzzle: {err}");
+    }
+}
+
+/// Downloads one puzzle from `url`, with `{difficulty}` replaced by the name
+/// of `difficulty` for APIs that take it.
+fn download(url: &str, difficulty: Difficulty) -> Result<Board, Box<dyn Error>> {
+    let url = url.replace("{difficulty}", &difficulty.to_string());
+    let text = ureq::get(&url).timeout(TIMEOUT).call()?.into_string()?;
+    puzzle_in(&text).ok_or_else(|| "the response has no puzzle in it".into())
+}
+
+/// Finds the puzzle in an API's response: plain text holding a board, or
+/// JSON with the board somewhere in it as a line of 81 cells or as nine rows
+/// of numbers with 0 for empty cells.
+fn puzzle_in(text: &str) -> Option<Board> {
+    match serde_json::from_str(text) {
+        Ok(value) => find_puzzle(&value),
+        Err(_) => text.parse().ok()
+    }
+}
+
+fn find_puzzle(value: &Value) -> Option<Board> {
+    // APIs send the solution along, which has to be told from the puzzle
+    let puzzle = |board: Board| (!board.is_full()).then_some(board);
+    match value {
+        Value::String(text) => text.parse().ok().and_then(puzzle),
+        Value::Array(rows) => rows_to_board(rows).and_then(puzzle).or_else(|| rows.iter().find_map(find_puzzle)),
+        Value::Object(fields) => fields.values().find_map(find_puzzle),
+        _ => None
+    }
+}
+
+fn rows_to_board(rows: &[Value]) -> Option<Board> {
+    if rows.len() != 9 {
+        return None;
+    }
+    let mut line = String::with_capacity(81);
+    for row in rows {
+        let cells = row.as_array().filter(|cells| cells.len() == 9)?;
+        for cell in cells {
+            let digit = cell.as_u64().filter(|&digit| digit <= 9)?;
+            line.push(char::from(b'0' + digit as u8));
+        }
+    }
+    line.parse().ok()
+}
+
+/// A puzzle being downloaded on a worker thread, so the window stays responsive.
+pub struct Download {
+    pub difficulty: Difficulty,
+    started: Instant,
+    receiver: Receiver<Result<Board, String>>
+}
+
+impl Download {
+    /// Starts looking for a puzzle of `difficulty`, first among those cached
+    /// from earlier downloads and then at `url`.
+    ///
+    /// Puzzles are rated here rather than trusting the API, so downloads of
+    /// another difficulty are cached for when that one is asked for.
+    pub fn start(url: &str, difficulty: Difficulty) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let url = url.to_string();
+        thread::spawn(move || {
+            if let Some(board) = take_cached(difficulty) {
+                let _ = sender.send(Ok(board));
+                return;
+            }
+            for _ in 0..MAX_DOWNLOADS {
+                let board = match download(&url, difficulty) {
+                    Ok(board) => board,
+                    Err(err) => {
+                        let _ = sender.send(Err(err.to_string()));
+                        return;
+                    }
+                };
+                match rate(&board) {
+                    Some(rated) if rated == difficulty => {
+                        let _ = sender.send(Ok(board));
+                        return;
+                    },
+                    Some(rated) => cache(&board, rated),
+                    None => ()
+                }
+            }
+            let _ = sender.send(Err(format!("none of {MAX_DOWNLOADS} puzzles downloaded was {difficulty}, try again")));
+        });
+
+        Self { difficulty, started: Instant::now(), receiver }
+    }
+
+    /// The puzzle, or why there isn't one, once the worker is done.
+    pub fn poll(&mut self) -> Option<Result<Board, String>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("the download stopped".to_string()))
+        }
+    }
+
+    /// A spinning line to show that the worker is busy.
+    pub fn spinner(&self) -> char {
+        ['|', '/', '-', '\\'][(self.started.elapsed().as_millis() / 150 % 4) as usize]
+    }
+}
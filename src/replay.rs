@@ -0,0 +1,100 @@
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fs,
+    path::Path,
+    time::{Duration, Instant}
+};
+
+use serde::{Deserialize, Serialize};
+use sudoku_core::Board;
+
+use crate::Action;
+
+/// Something that happened during a recorded session.
+#[derive(Serialize, Deserialize)]
+pub enum Event {
+    Action(Action),
+    /// The board was replaced from outside the recording, as by pasting or
+    /// generating a puzzle, so the replay needs the result rather than the key.
+    Board(Box<Board>, (i8, i8)),
+    /// The random solving order was switched on with this seed, or off.
+    Seed(Option<u64>)
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// Milliseconds since the recording started.
+    time: u64,
+    event: Event
+}
+
+/// A recorded session: where it started and everything that happened since.
+#[derive(Serialize, Deserialize)]
+pub struct Replay {
+    pub board: Board,
+    pub cursor: (i8, i8),
+    pub visual_solving: bool,
+    entries: Vec<Entry>
+}
+
+/// Collects the events of the running session for `--record`.
+pub struct Recorder {
+    started: Instant,
+    replay: Replay
+}
+
+impl Recorder {
+    pub fn new(board: Board, cursor: (i8, i8), visual_solving: bool) -> Self {
+        Self { started: Instant::now(), replay: Replay { board, cursor, visual_solving, entries: Vec::new() } }
+    }
+
+    pub fn record(&mut self, event: Event) {
+        let time = self.started.elapsed().as_millis() as u64;
+        self.replay.entries.push(Entry { time, event });
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string(&self.replay)?)?;
+        Ok(())
+    }
+}
+
+/// Hands out the events of a recorded session at the times they happened.
+pub struct Player {
+    started: Instant,
+    entries: VecDeque<Entry>
+}
+
+impl Player {
+    pub fn new(replay: Replay) -> Self {
+        Self { started: Instant::now(), entries: replay.entries.into() }
+    }
+
+    /// The events whose time has come since the last call.
+    pub fn due(&mut self) -> Vec<Event> {
+        let now = self.started.elapsed();
+        let mut events = Vec::new();
+        while let Some(entry) = self.entries.front() && Duration::from_millis(entry.time) <= now {
+            events.extend(self.entries.pop_front().map(|entry| entry.event));
+        }
+        events
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+pub fn load(path: &Path) -> Result<Replay, Box<dyn Error>> {
+    let replay: Replay = serde_json::from_str(&fs::read_to_string(path)?)?;
+    // Keys only ever write digits, so anything else wasn't recorded by us
+    for entry in &replay.entries {
+        if let Event::Action(Action::Write(num) | Action::ToggleNote(num) | Action::PickDigit(num)) = entry.event
+            && !(1..=9).contains(&num)
+        {
+            return Err(format!("the replay writes {num}, which isn't a digit").into());
+        }
+    }
+    Ok(replay)
+}
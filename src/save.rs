@@ -0,0 +1,40 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf}
+};
+
+use serde::{Deserialize, Serialize};
+use sudoku_core::{Board, Coloring};
+
+/// Where F5 and F9 save and load the game.
+pub const SAVE_PATH: &str = "game.json";
+
+/// How often the game is saved automatically while it changes.
+pub const AUTOSAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// The file the game is saved to automatically, in the platform's data
+/// directory for the app. Returns `None` if SDL can't find or create it.
+pub fn autosave_path() -> Option<PathBuf> {
+    let dir = sdl2::filesystem::pref_path("GardSvard", "sudoku-solver").ok()?;
+    Some(Path::new(&dir).join("autosave.json"))
+}
+
+/// Everything needed to pick a game up where it was left.
+#[derive(PartialEq, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub board: Board,
+    pub cursor: (i8, i8),
+    /// Missing from games saved before cells could be colored.
+    #[serde(default)]
+    pub coloring: Coloring
+}
+
+pub fn save(path: &Path, game: &SavedGame) -> Result<(), Box<dyn Error>> {
+    fs::write(path, serde_json::to_string_pretty(game)?)?;
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<SavedGame, Box<dyn Error>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
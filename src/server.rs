@@ -0,0 +1,148 @@
+use std::{
+    error::Error,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc
+    },
+    thread,
+    time::{Duration, Instant}
+};
+
+use serde_json::{json, Value};
+use sudoku_core::{count_solutions, default_threads, find_solution, generate_rated, rate, to_share_code, Board, Difficulty, Rng};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::files;
+
+/// How long `/generate` looks for a puzzle of the asked difficulty before giving up.
+const GENERATE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// `serve [--port N] [--host ADDR] [--threads N]`
+///
+/// Answers HTTP requests with JSON until stopped, so the solver can back a web
+/// page or a bot:
+///
+/// - `POST /solve` with a puzzle returns `{"solution": ..., "unique": ...}`
+/// - `POST /grade` with a puzzle returns `{"difficulty": ..., "clues": ..., "solutions": ...}`,
+///   counting solutions up to 2
+/// - `GET /generate?difficulty=hard` returns `{"puzzle": ..., "difficulty": ..., "clues": ..., "share_code": ...}`
+///
+/// A puzzle is sent as `{"puzzle": "..."}` or as the bare text, in any form
+/// the window's `i` key takes. Errors come back as `{"error": ...}` with a
+/// 4xx or 5xx status.
+pub fn serve(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut port = 8080;
+    let mut host = "127.0.0.1".to_string();
+    let mut threads = default_threads();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--port" => port = value()?.parse()?,
+            "--host" => host = value()?.clone(),
+            "--threads" => threads = value()?.parse()?,
+            _ => return Err(format!("unknown argument {arg}").into())
+        }
+    }
+
+    let server = Arc::new(Server::http((host.as_str(), port)).map_err(|err| format!("couldn't listen on {host}:{port}: {err}"))?);
+    eprintln!("Listening on http://{host}:{port} with {threads} threads");
+    let workers: Vec<_> = (0..threads.max(1)).map(|_| {
+        let server = Arc::clone(&server);
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                handle(request);
+            }
+        })
+    }).collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    Ok(())
+}
+
+fn handle(mut request: Request) {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let (status, body) = match (request.method(), path) {
+        // Browsers ask before posting JSON from another origin
+        (Method::Options, _) => (204, Value::Null),
+        (Method::Post, "/solve") => reply(read_puzzle(&mut request).and_then(solve)),
+        (Method::Post, "/grade") => reply(read_puzzle(&mut request).map(grade)),
+        (Method::Get, "/generate") => reply(generate(query)),
+        (_, "/solve" | "/grade" | "/generate") => (405, json!({ "error": "method not allowed" })),
+        _ => (404, json!({ "error": "no such endpoint" }))
+    };
+
+    let body = if body.is_null() { String::new() } else { body.to_string() };
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+        .with_header(header("Access-Control-Allow-Origin", "*"))
+        .with_header(header("Access-Control-Allow-Methods", "GET, POST, OPTIONS"))
+        .with_header(header("Access-Control-Allow-Headers", "Content-Type"));
+    if let Err(err) = request.respond(response) {
+        eprintln!("Couldn't answer {url}: {err}");
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).expect("header names and values are ASCII")
+}
+
+fn reply(result: Result<Value, (u16, String)>) -> (u16, Value) {
+    match result {
+        Ok(body) => (200, body),
+        Err((status, error)) => (status, json!({ "error": error }))
+    }
+}
+
+fn read_puzzle(request: &mut Request) -> Result<Board, (u16, String)> {
+    let mut body = String::new();
+    request.as_reader().read_to_string(&mut body).map_err(|err| (400, err.to_string()))?;
+    let text = match serde_json::from_str::<Value>(&body) {
+        Ok(value) => value.get("puzzle").and_then(Value::as_str).ok_or((400, "expected {\"puzzle\": \"...\"}".to_string()))?.to_string(),
+        Err(_) => body
+    };
+    files::parse_board(&text).map_err(|err| (400, err.to_string()))
+}
+
+fn solve(board: Board) -> Result<Value, (u16, String)> {
+    if !board.is_valid() {
+        return Err((422, "the puzzle breaks the rules".to_string()));
+    }
+    let solution = find_solution(&board).ok_or((422, "the puzzle has no solution".to_string()))?;
+    Ok(json!({ "solution": solution.to_line(), "unique": count_solutions(&board, 2) == 1 }))
+}
+
+fn grade(board: Board) -> Value {
+    let solutions = if board.is_valid() { count_solutions(&board, 2) } else { 0 };
+    json!({
+        "difficulty": rate(&board).map(|difficulty| difficulty.to_string()),
+        "clues": board.clues(),
+        "solutions": solutions
+    })
+}
+
+fn generate(query: &str) -> Result<Value, (u16, String)> {
+    let name = query.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find_map(|(key, value)| (key == "difficulty").then_some(value))
+        .unwrap_or("medium");
+    let difficulty = Difficulty::from_name(name).ok_or_else(|| (400, format!("unknown difficulty {name}")))?;
+
+    let start = Instant::now();
+    let cancel = AtomicBool::new(false);
+    let generated = generate_rated(difficulty, &mut Rng::from_entropy(), &cancel, |_| {
+        if start.elapsed() > GENERATE_TIMEOUT {
+            cancel.store(true, Ordering::Relaxed);
+        }
+    }).ok_or_else(|| (503, format!("no {difficulty} puzzle found in {GENERATE_TIMEOUT:?}, try again")))?;
+    Ok(json!({
+        "puzzle": generated.puzzle.to_line(),
+        "difficulty": difficulty.to_string(),
+        "clues": generated.clues,
+        "share_code": to_share_code(&generated.puzzle)
+    }))
+}
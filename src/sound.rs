@@ -0,0 +1,116 @@
+//! Short sound effects, made up from tones rather than read from files.
+//! Only builds with the `sound` feature play them, as they need SDL2_mixer.
+
+#[cfg(feature = "sound")]
+use sdl2::mixer::{self, Channel, Chunk};
+
+/// Something worth a sound.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Effect {
+    /// A digit written by hand.
+    Place,
+    /// A digit that doesn't match the solution.
+    Error,
+    /// The solver taking a digit back.
+    Backtrack,
+    /// The board solved.
+    Solved
+}
+
+/// The sound effects, ready to play.
+pub struct Sounds {
+    muted: bool,
+    #[cfg(feature = "sound")]
+    chunks: Vec<(Effect, Chunk)>,
+    /// Kept open for as long as sounds can play.
+    #[cfg(feature = "sound")]
+    _audio: Option<sdl2::AudioSubsystem>
+}
+
+impl Sounds {
+    /// Opens the audio device and makes the effects at `volume`, from 0 to 100.
+    /// Without a device, or in builds without sound, playing does nothing.
+    #[cfg_attr(not(feature = "sound"), allow(unused_variables))]
+    pub fn open(sdl: &sdl2::Sdl, volume: u8, muted: bool) -> Self {
+        #[cfg(feature = "sound")]
+        {
+            match open_chunks(sdl, volume) {
+                Ok((audio, chunks)) => Self { muted, chunks, _audio: Some(audio) },
+                Err(err) => {
+                    println!("Couldn't open the audio device, so there will be no sound: {err}");
+                    Self { muted, chunks: Vec::new(), _audio: None }
+                }
+            }
+        }
+        #[cfg(not(feature = "sound"))]
+        Self { muted }
+    }
+
+    #[cfg_attr(not(feature = "sound"), allow(unused_variables))]
+    pub fn play(&self, effect: Effect) {
+        #[cfg(feature = "sound")]
+        if !self.muted && let Some((_, chunk)) = self.chunks.iter().find(|(kind, _)| *kind == effect) {
+            let _ = Channel::all().play(chunk, 0);
+        }
+    }
+
+    /// Mutes the sounds or turns them back on, returning whether they are muted now.
+    pub fn toggle_mute(&mut self) -> bool {
+        self.muted = !self.muted;
+        self.muted
+    }
+}
+
+/// A note of a sound effect, at `frequency` in hertz, starting `start`
+/// seconds in and dying away over `length` seconds.
+#[cfg(feature = "sound")]
+struct Note {
+    frequency: f32,
+    start: f32,
+    length: f32
+}
+
+#[cfg(feature = "sound")]
+fn open_chunks(sdl: &sdl2::Sdl, volume: u8) -> Result<(sdl2::AudioSubsystem, Vec<(Effect, Chunk)>), String> {
+    let audio = sdl.audio()?;
+    mixer::open_audio(44_100, mixer::AUDIO_S16SYS, 1, 512)?;
+    // The device may have opened with another rate or more channels than asked for
+    let (rate, _, channels) = mixer::query_spec()?;
+    Channel::all().set_volume(volume.min(100) as i32 * mixer::MAX_VOLUME / 100);
+
+    let note = |frequency, start, length| Note { frequency, start, length };
+    let effects = [
+        (Effect::Place, vec![note(1200.0, 0.0, 0.05)], false),
+        (Effect::Error, vec![note(150.0, 0.0, 0.25)], true),
+        (Effect::Backtrack, vec![note(2400.0, 0.0, 0.015)], false),
+        (Effect::Solved, vec![note(523.0, 0.0, 0.3), note(659.0, 0.1, 0.3), note(784.0, 0.2, 0.3), note(1047.0, 0.3, 0.5)], false)
+    ];
+    let mut chunks = Vec::new();
+    for (effect, notes, square) in effects {
+        let samples = synthesize(&notes, square, rate as u32, channels as usize);
+        chunks.push((effect, Chunk::from_raw_buffer(samples.into_boxed_slice())?));
+    }
+    Ok((audio, chunks))
+}
+
+/// The notes as 16-bit samples, each written once per channel. Square waves
+/// sound harsher than sine waves, for errors.
+#[cfg(feature = "sound")]
+fn synthesize(notes: &[Note], square: bool, rate: u32, channels: usize) -> Vec<i16> {
+    let length = notes.iter().map(|note| note.start + note.length).fold(0.0, f32::max);
+    let count = (length * rate as f32) as usize;
+    let mut samples = Vec::with_capacity(count * channels);
+    for i in 0..count {
+        let time = i as f32 / rate as f32;
+        let mut value = 0.0;
+        for note in notes.iter().filter(|note| (note.start..note.start + note.length).contains(&time)) {
+            let wave = (std::f32::consts::TAU * note.frequency * (time - note.start)).sin();
+            let wave = if square { wave.signum() * 0.5 } else { wave };
+            // Dies away, so notes don't end in a click
+            value += wave * (1.0 - (time - note.start) / note.length).powi(2);
+        }
+        let sample = (value.clamp(-1.0, 1.0) * i16::MAX as f32 * 0.5) as i16;
+        samples.extend(std::iter::repeat_n(sample, channels));
+    }
+    samples
+}
@@ -0,0 +1,140 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration
+};
+
+use serde::{Deserialize, Serialize};
+use sudoku_core::{rate, Board, Difficulty, Progress};
+
+use crate::timer;
+
+/// How the current game has gone since its puzzle was loaded or restarted.
+#[derive(Default)]
+pub struct GameStats {
+    /// Wrong digits entered.
+    pub mistakes: u32,
+    /// Hints taken and cells revealed.
+    pub hints: u32,
+    /// The solver's nodes and backtracks, when it finished the puzzle.
+    pub solver: Option<(u64, u64)>,
+    /// Whether the solver has shown every step it took since the board last
+    /// held only its givens.
+    pub watched: bool,
+    /// How hard the givens are, once the game is over.
+    pub difficulty: Option<Difficulty>
+}
+
+impl GameStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notes how the game ended, with the solver's progress if it filled the board.
+    pub fn finish(&mut self, board: &Board, solver: Option<&Progress>) {
+        let mut givens = *board;
+        givens.clear_soft();
+        self.difficulty = rate(&givens);
+        self.solver = solver.map(|progress| (progress.nodes, progress.backtracks));
+    }
+
+    /// The lines shown over the board once the game took `time`.
+    pub fn lines(&self, time: Duration) -> Vec<String> {
+        let mut lines = vec![match self.solver {
+            Some(_) => "Solver finished".to_string(),
+            None => "Solved!".to_string()
+        }];
+        lines.push(format!("Time {}", timer::clock(time)));
+        if let Some(difficulty) = self.difficulty {
+            lines.push(format!("Rated {difficulty}"));
+        }
+        lines.push(format!("{} hints", self.hints));
+        lines.push(format!("{} mistakes", self.mistakes));
+        if let Some((nodes, backtracks)) = self.solver {
+            lines.push(format!("{nodes} nodes"));
+            lines.push(format!("{backtracks} backtracks"));
+        }
+        lines.push("Enter: new puzzle".to_string());
+        lines
+    }
+}
+
+/// The file the lifetime stats are kept in, in the platform's data directory
+/// for the app. Returns `None` if SDL can't find or create it.
+pub fn records_path() -> Option<PathBuf> {
+    let dir = sdl2::filesystem::pref_path("GardSvard", "sudoku-solver").ok()?;
+    Some(Path::new(&dir).join("records.json"))
+}
+
+/// Totals over every game played, kept between runs.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Records {
+    /// Games that ended, whoever filled the board.
+    pub played: u32,
+    /// Games the player filled the board in.
+    pub won: u32,
+    /// The fastest win in seconds at each difficulty, easiest first.
+    pub best_times: [Option<u64>; 4],
+    /// The day of the latest daily puzzle won, counted from the Unix epoch.
+    pub last_daily: Option<u64>,
+    /// Daily puzzles won on days in a row, up to `last_daily`.
+    pub streak: u32,
+    pub best_streak: u32
+}
+
+impl Records {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Counts a game that ended after `time`, won or not, which was the
+    /// puzzle of `daily` if that is set.
+    pub fn add(&mut self, game: &GameStats, won: bool, time: Duration, daily: Option<u64>) {
+        self.played += 1;
+        if !won {
+            return;
+        }
+        self.won += 1;
+        if let Some(difficulty) = game.difficulty {
+            let best = &mut self.best_times[difficulty as usize];
+            *best = Some(best.map_or(time.as_secs(), |best| best.min(time.as_secs())));
+        }
+        // Winning the same day's puzzle again doesn't add to the streak
+        if let Some(day) = daily.filter(|&day| self.last_daily.is_none_or(|last| last < day)) {
+            self.streak = if self.last_daily == Some(day - 1) { self.streak + 1 } else { 1 };
+            self.best_streak = self.best_streak.max(self.streak);
+            self.last_daily = Some(day);
+        }
+    }
+
+    /// The streak as it stands on `today`, gone if yesterday's puzzle was missed.
+    pub fn streak(&self, today: u64) -> u32 {
+        match self.last_daily {
+            Some(last) if last + 1 >= today => self.streak,
+            _ => 0
+        }
+    }
+
+    /// The lines of the stats page on `today`.
+    pub fn lines(&self, today: u64) -> Vec<String> {
+        let mut lines = vec![
+            format!("Won {} of {}", self.won, self.played),
+            format!("Streak {}, best {}", self.streak(today), self.best_streak)
+        ];
+        let difficulties = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Expert];
+        for (difficulty, best) in difficulties.into_iter().zip(self.best_times) {
+            if let Some(best) = best {
+                lines.push(format!("Best {difficulty} {}", timer::clock(Duration::from_secs(best))));
+            }
+        }
+        lines.push("Any key: close".to_string());
+        lines
+    }
+}
@@ -1,13 +1,31 @@
 
+use std::collections::{hash_map::Entry, HashMap};
+
+use sdl2::{
+    pixels::Color,
+    rect::Rect,
+    render::{Texture, TextureCreator, WindowCanvas},
+    ttf::Font,
+    video::WindowContext
+};
+use sudoku_core::render::{status_height, Drawn, Rgb, Surface, PANEL_WIDTH};
+
 const TITLE: &str = "Sudoku Solver";
+/// The default size of one cell, which the config file can change.
 pub const TILE_SIZE: u32 = 30;
-pub const LOGICAL_WIDTH: u32 = TILE_SIZE * 9;
-pub const LOGICAL_HEIGHT: u32 = TILE_SIZE * 9;
+/// The default scale, which the config file can change.
 pub const SCALE: u32 = 3;
-pub const INT_SCALE: bool = true;
+/// The smallest cells the window can be shrunk to.
+const MIN_TILE_SIZE: u32 = 12;
 pub const BLEND_MODE: sdl2::render::BlendMode = sdl2::render::BlendMode::None;
+/// How many times per second the window is redrawn, which the config file can change.
+pub const FPS: u32 = 60;
 
-const FONT_PATH: &str = "assets/minecraft.otf";
+pub const FONT_PATH: &str = "assets/minecraft.otf";
+/// Built into the program for when there is no font at the configured path,
+/// as when it runs from outside the repository.
+const FALLBACK_FONT: &[u8] = include_bytes!("../assets/DejaVuSansMono.ttf");
+/// The font size for the default tile size, scaled along with it.
 const FONT_SIZE: u16 = 20;
 
 /// Contains the various components of SDL2 used by the game.
@@ -18,32 +36,246 @@ pub struct SdlContext {
     pub canvas: sdl2::render::WindowCanvas,
     /// The SDL2 texture creator for rendering text and copying images.
     pub texture_creator: sdl2::render::TextureCreator<sdl2::video::WindowContext>,
+    /// The size of one cell on the canvas.
+    pub tile_size: u32,
+}
+
+impl SdlContext {
+    /// The width and height of the board on the canvas.
+    pub fn board_size(&self) -> u32 {
+        self.tile_size * 9
+    }
+
+    /// Fits the largest cells that leave room for everything into the window
+    /// as it is now, counting the display's own pixels, which on high-DPI
+    /// screens are more than the window's size says. Returns whether the cells
+    /// changed size, in which case the font needs loading again at the new size.
+    pub fn fit_window(&mut self) -> bool {
+        let Ok((width, height)) = self.canvas.output_size() else {
+            return false;
+        };
+        let mut tile_size = (width / (9 + PANEL_WIDTH)).max(MIN_TILE_SIZE);
+        while tile_size > MIN_TILE_SIZE && canvas_size(tile_size).1 > height {
+            tile_size -= 1;
+        }
+        if tile_size == self.tile_size {
+            return false;
+        }
+        self.tile_size = tile_size;
+        let (width, height) = canvas_size(tile_size);
+        let _ = self.canvas.set_logical_size(width, height);
+        true
+    }
+}
+
+/// The size of the board with the panel to its right and the status bar under both.
+fn canvas_size(tile_size: u32) -> (u32, u32) {
+    (tile_size * (9 + PANEL_WIDTH), tile_size * 9 + status_height(tile_size))
+}
+
+/// The digits already rendered in the font, by color, so frames copy them
+/// rather than rendering every digit again. Needs clearing whenever the font
+/// is loaded again.
+pub struct Glyphs<'t> {
+    texture_creator: &'t TextureCreator<WindowContext>,
+    textures: HashMap<(char, Rgb), Texture<'t>>
+}
+
+impl<'t> Glyphs<'t> {
+    pub fn new(texture_creator: &'t TextureCreator<WindowContext>) -> Self {
+        Self { texture_creator, textures: HashMap::new() }
+    }
+
+    pub fn clear(&mut self) {
+        self.textures.clear();
+    }
+}
+
+/// `text` rendered in `font`, or `None` if the font couldn't render it.
+fn render_text<'t>(texture_creator: &'t TextureCreator<WindowContext>, font: &Font, text: &str, color: Rgb) -> Option<Texture<'t>> {
+    let surface = font.render(text).blended(Color::RGB(color.0, color.1, color.2)).ok()?;
+    texture_creator.create_texture_from_surface(&surface).ok()
+}
+
+/// What was drawn on the last frame, kept in a texture so that the next one
+/// only needs to draw the cells that changed over it.
+pub struct Frame<'t> {
+    texture_creator: &'t TextureCreator<WindowContext>,
+    texture: Option<Texture<'t>>,
+    pub drawn: Drawn
+}
+
+impl<'t> Frame<'t> {
+    pub fn new(texture_creator: &'t TextureCreator<WindowContext>) -> Self {
+        Self { texture_creator, texture: None, drawn: Drawn::new() }
+    }
+
+    /// Draws with `draw` over the last frame, told what that showed, and
+    /// copies the result onto `canvas`. The texture is made again, blank, when
+    /// cells of `tile_size` no longer fit it, as after the window is resized.
+    /// Where textures can't be drawn on, every frame is drawn on `canvas` in full.
+    pub fn draw(&mut self, canvas: &mut WindowCanvas, tile_size: u32, draw: impl FnOnce(&mut WindowCanvas, &mut Drawn)) {
+        let (width, height) = canvas_size(tile_size);
+        let fits = self.texture.as_ref().is_some_and(|texture| {
+            let query = texture.query();
+            (query.width, query.height) == (width, height)
+        });
+        if !fits {
+            self.drawn.forget();
+            self.texture = self.texture_creator.create_texture_target(None, width, height).ok();
+            if let Some(texture) = &mut self.texture {
+                texture.set_blend_mode(BLEND_MODE);
+            }
+        }
+
+        let mut draw = Some(draw);
+        if let Some(texture) = &mut self.texture {
+            let drawn = &mut self.drawn;
+            let drew = canvas.with_texture_canvas(texture, |target| {
+                if let Some(draw) = draw.take() {
+                    draw(target, drawn);
+                }
+            });
+            if drew.is_ok() {
+                let _ = canvas.copy(texture, None, None);
+                return;
+            }
+        }
+        if let Some(draw) = draw {
+            self.drawn.forget();
+            draw(canvas, &mut self.drawn);
+            self.drawn.forget();
+        }
+    }
+}
+
+/// A canvas as a [`Surface`] for the board to be drawn on, writing text in
+/// `font` with the digits taken from `glyphs`.
+pub struct SdlSurface<'a, 'ttf, 'r, 't> {
+    pub canvas: &'a mut WindowCanvas,
+    pub tile_size: u32,
+    pub font: &'a Font<'ttf, 'r>,
+    pub glyphs: &'a mut Glyphs<'t>
+}
+
+impl Surface for SdlSurface<'_, '_, '_, '_> {
+    fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32, color: Rgb) {
+        self.canvas.set_draw_color(Color::RGB(color.0, color.1, color.2));
+        let _ = self.canvas.fill_rect(Rect::new(x, y, width, height));
+    }
+
+    fn text(&mut self, text: &str, center: (i32, i32), color: Rgb) {
+        self.text_scaled(text, center, color, 1);
+    }
+
+    fn small_text(&mut self, text: &str, center: (i32, i32), color: Rgb) {
+        self.text_scaled(text, center, color, 3);
+    }
+}
+
+impl SdlSurface<'_, '_, '_, '_> {
+    /// Draws `text` at `1 / divisor` of the font's size, shrinking the rendered
+    /// text rather than loading the font again at every size.
+    fn text_scaled(&mut self, text: &str, center: (i32, i32), color: Rgb, divisor: u32) {
+        let (mut chars, texture_creator) = (text.chars(), self.glyphs.texture_creator);
+        // Single digits, which most of the text on the board is, come from the cache
+        let rendered;
+        let texture = match (chars.next(), chars.next()) {
+            (Some(digit), None) if digit.is_ascii_digit() => match self.glyphs.textures.entry((digit, color)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let Some(texture) = render_text(texture_creator, self.font, text, color) else {
+                        return;
+                    };
+                    entry.insert(texture)
+                }
+            },
+            _ => {
+                let Some(texture) = render_text(texture_creator, self.font, text, color) else {
+                    return;
+                };
+                rendered = texture;
+                &rendered
+            }
+        };
+        let sdl2::render::TextureQuery { width, height, .. } = texture.query();
+        let (width, height) = (width / divisor, height / divisor);
+        // The font sits a little high and left of where it says it does
+        let (dx, dy) = (1 / divisor as i32, 2 / divisor as i32);
+        let target = Rect::new(center.0 - width as i32 / 2 + dx, center.1 - height as i32 / 2 + dy, width, height);
+        let _ = self.canvas.copy(texture, None, Some(target));
+    }
 }
 
 /// A convenience function for initializing SDL2 with reasonable default settings.
+///
+/// The window starts out `scale` times the size of cells of `tile_size` and
+/// can be resized, with the cells following its size rather than the pixels
+/// being scaled up. A `hidden` window can still be drawn to, for exporting
+/// pictures without showing anything. With `vsync`, showing a frame waits
+/// for the display to be ready for it.
 pub fn init_sdl_systems(
     sdl: &sdl2::Sdl,
     video: &sdl2::VideoSubsystem,
+    hidden: bool,
+    tile_size: u32,
+    scale: u32,
+    vsync: bool,
 ) -> Result<SdlContext, Box<dyn std::error::Error>> {
     let events = sdl.event_pump()?;
-    let window = video
-        .window(TITLE, LOGICAL_WIDTH * SCALE, LOGICAL_HEIGHT * SCALE)
-        .build()?;
-    let mut canvas = window.into_canvas().build()?;
-    canvas.set_logical_size(LOGICAL_WIDTH, LOGICAL_HEIGHT)?;
-    canvas.set_integer_scale(INT_SCALE)?;
+    let tile_size = tile_size * scale;
+    let (width, height) = canvas_size(tile_size);
+    let mut window = video.window(TITLE, width, height);
+    window.resizable().allow_highdpi();
+    if hidden {
+        window.hidden();
+    }
+    let window = window.build()?;
+    let mut canvas = match vsync {
+        true => window.into_canvas().present_vsync().build()?,
+        false => window.into_canvas().build()?
+    };
+    // Keeps the layout's proportions, leaving bars at the sides if the window's differ
+    canvas.set_logical_size(width, height)?;
     canvas.set_blend_mode(BLEND_MODE);
 
     let texture_creator = canvas.texture_creator();
 
-    Ok(SdlContext {
+    let mut ctx = SdlContext {
         events,
         canvas,
         texture_creator,
-    })
+        tile_size,
+    };
+    // Drawn at the display's resolution, so text stays sharp on high-DPI screens
+    ctx.fit_window();
+    Ok(ctx)
+}
+
+/// Shows `status` after the program name in the window title, or just the name if `None`.
+pub fn set_title_status(ctx: &mut SdlContext, status: Option<&str>) {
+    let title = match status {
+        Some(status) => format!("{TITLE} - {status}"),
+        None => TITLE.to_string()
+    };
+    let _ = ctx.canvas.window_mut().set_title(&title);
+}
+
+/// How long each frame lasts at `fps` frames per second.
+pub fn frame_time(fps: u32) -> std::time::Duration {
+    std::time::Duration::from_nanos(1_000_000_000 / fps.max(1) as u64)
 }
 
-/// Load the default font from the asset folder.
-pub fn load_font(ttf: &sdl2::ttf::Sdl2TtfContext) -> Result<sdl2::ttf::Font<'_, 'static>, String> {
-    ttf.load_font(FONT_PATH, FONT_SIZE)
+/// Load the font at `path`, sized to fit cells of `tile_size`, or the built-in
+/// font if there is no file there.
+pub fn load_font<'ttf>(ttf: &'ttf sdl2::ttf::Sdl2TtfContext, path: &std::path::Path, tile_size: u32) -> Result<sdl2::ttf::Font<'ttf, 'static>, String> {
+    let size = (FONT_SIZE as u32 * tile_size / TILE_SIZE) as u16;
+    if path.exists() {
+        return ttf.load_font(path, size);
+    }
+    ttf.load_font_from_rwops(sdl2::rwops::RWops::from_bytes(FALLBACK_FONT)?, size)
 }
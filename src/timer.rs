@@ -0,0 +1,59 @@
+use std::time::{Duration, Instant};
+
+/// How long the current puzzle has been played, leaving out pauses.
+pub struct Timer {
+    /// The time played up to the latest pause.
+    played: Duration,
+    /// When play last started or resumed, while the clock runs.
+    since: Option<Instant>,
+    /// Set once the puzzle is solved, after which the clock stays stopped.
+    stopped: bool
+}
+
+impl Timer {
+    /// A clock running from zero.
+    pub fn start() -> Self {
+        Self { played: Duration::ZERO, since: Some(Instant::now()), stopped: false }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.played + self.since.map_or(Duration::ZERO, |since| since.elapsed())
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.since.is_none() && !self.stopped
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Pauses a running clock or resumes a paused one. A stopped clock stays stopped.
+    pub fn toggle_pause(&mut self) {
+        if self.stopped {
+            return;
+        }
+        match self.since.take() {
+            Some(since) => self.played += since.elapsed(),
+            None => self.since = Some(Instant::now())
+        }
+    }
+
+    /// Stops the clock for good, as when the puzzle is solved.
+    pub fn stop(&mut self) {
+        if let Some(since) = self.since.take() {
+            self.played += since.elapsed();
+        }
+        self.stopped = true;
+    }
+}
+
+/// Writes `duration` as minutes and seconds, like `4:05`, with hours in front
+/// once there are any.
+pub fn clock(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{hours}:{:02}:{:02}", seconds / 60 % 60, seconds % 60)
+    }
+}
@@ -0,0 +1,455 @@
+//! A terminal frontend with the same keys as the window, for machines without
+//! SDL2 or a display. Built with `cargo run --release --features tui --bin sudoku-tui`.
+
+use std::{
+    error::Error,
+    io::{self, Stdout, Write},
+    time::{Duration, Instant, SystemTime}
+};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen}
+};
+use sudoku_core::{
+    box_index, count_solutions, daily_puzzle, fill_unit, minimize, Board, CellOrder, Difficulty, Progress, Rng, SolveLog,
+    Solver, SolverOptions, Step, StepResult, Tile, Unit
+};
+
+use fixtures::test_board;
+use generation::Generation;
+
+#[path = "../fixtures.rs"]
+mod fixtures;
+#[path = "../generation.rs"]
+mod generation;
+
+/// The visual solving speeds to choose from with `+` and `-`, in steps per second.
+const SPEEDS: &[u32] = &[1, 2, 5, 10, 20, 60, 120, 300, 600, 1200, 3000, 6000, 12000];
+const DEFAULT_SPEED: usize = 5;
+/// How many steps to take between checks for input when solving without visuals.
+const UNTHROTTLED_STEPS: u32 = 4096;
+/// Terminals can't keep up with as many frames as a window.
+const FRAME_TIME: Duration = Duration::from_millis(33);
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    let board = match args.get(1) {
+        Some(puzzle) => puzzle.parse()?,
+        None => Board::new()
+    };
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, Hide)?;
+    let result = run(board, &mut stdout);
+    // Give the terminal back even if the frontend failed
+    let _ = execute!(stdout, Show, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+    result
+}
+
+fn run(mut board: Board, stdout: &mut Stdout) -> Result<(), Box<dyn Error>> {
+    let mut cursor_index: (i8, i8) = (0, 0);
+    let mut running = true;
+    let mut solving = false;
+    let mut step_once = false;
+    let mut finished = false;
+    let mut unsolvable = false;
+    let mut visual_solving = true;
+    let mut region = None;
+    let mut speed = DEFAULT_SPEED;
+    let mut step_debt = 0.0;
+    let mut solver_options = SolverOptions::default();
+    let mut solver = solver_options.build();
+    let mut last_step = None;
+    let mut log = SolveLog::new();
+    let mut progress = Progress::new(&board);
+    let mut solutions = count_solutions(&board, 2);
+    let mut difficulty = Difficulty::Medium;
+    let mut generation: Option<Generation> = None;
+    // What the window version prints to the terminal is shown under the grid instead
+    let mut message = String::new();
+
+    let mut last_frame = Instant::now();
+    while running {
+        let frame_start = Instant::now();
+        let frame_delta = frame_start - last_frame;
+        last_frame = frame_start;
+
+        let steps = if step_once {
+            1
+        } else if !solving {
+            0
+        } else if visual_solving {
+            step_debt += SPEEDS[speed] as f64 * frame_delta.as_secs_f64();
+            let steps = step_debt as u32;
+            step_debt -= steps as f64;
+            steps
+        } else {
+            UNTHROTTLED_STEPS
+        };
+        step_once = false;
+
+        for _ in 0..steps {
+            if finished {
+                break;
+            }
+            match advance(solver.as_mut(), &mut board, &mut progress, &mut log) {
+                StepResult::Solving(step) => last_step = Some(step),
+                result => {
+                    solving = false;
+                    finished = true;
+                    unsolvable = matches!(result, StepResult::Unsolvable);
+                    last_step = None;
+                }
+            }
+        }
+
+        let mut edited = false;
+        if let Some(puzzle) = generation.as_mut().and_then(Generation::poll) {
+            board = puzzle;
+            generation = None;
+            edited = true;
+        }
+
+        // Waiting for input is what paces the frames
+        let wait = if solving && !visual_solving { Duration::ZERO } else { FRAME_TIME.saturating_sub(frame_start.elapsed()) };
+        for action in handle_input(wait, &mut running)? {
+            match action {
+                Action::Move(x, y) => cursor_index = ((cursor_index.0 + x).clamp(0, 8), (cursor_index.1 + y).clamp(0, 8)),
+                Action::Solve => {
+                    if board.is_valid() {
+                        solving = !solving;
+                    }
+                },
+                Action::StepOnce => {
+                    if board.is_valid() && !solving {
+                        step_once = true;
+                    }
+                },
+                Action::StepBack => {
+                    solving = false;
+                    if let Some(step) = log.rewind(&mut board) {
+                        progress.unrecord(step);
+                        last_step = Some(step.clone());
+                        finished = false;
+                        unsolvable = false;
+                    }
+                },
+                Action::Write(num) => {
+                    board.set((cursor_index.0 as usize, cursor_index.1 as usize), Tile::Hard(num));
+                    edited = true;
+                },
+                Action::Remove => {
+                    board.set((cursor_index.0 as usize, cursor_index.1 as usize), Tile::Empty);
+                    edited = true;
+                },
+                Action::CycleRegion => {
+                    region = match region {
+                        None => Some(Region::Row),
+                        Some(Region::Row) => Some(Region::Column),
+                        Some(Region::Column) => Some(Region::Box),
+                        Some(Region::Box) => None
+                    };
+                },
+                Action::FillRegion => {
+                    if let Some(region) = region {
+                        let unit = region.unit(cursor_index);
+                        let placed = fill_unit(&mut board, unit, solver_options.techniques);
+                        message = match placed.len() {
+                            0 => format!("Nothing more in {unit} follows from logic alone"),
+                            count => format!("Filled {count} cells in {unit}")
+                        };
+                        solving = false;
+                        edited = true;
+                    }
+                },
+                Action::ToggleVisual => {
+                    visual_solving = !visual_solving;
+                    message = format!("Visual solving {}", if visual_solving { "on" } else { "off" });
+                },
+                Action::Faster => {
+                    speed = (speed + 1).min(SPEEDS.len() - 1);
+                    message = format!("Speed: {} steps per second", SPEEDS[speed]);
+                },
+                Action::Slower => {
+                    speed = speed.saturating_sub(1);
+                    message = format!("Speed: {} steps per second", SPEEDS[speed]);
+                },
+                Action::ToggleOrder => {
+                    solver_options.order = match solver_options.order {
+                        CellOrder::Raster => CellOrder::MostConstrained,
+                        CellOrder::MostConstrained => CellOrder::Raster
+                    };
+                    message = format!("Cell order: {:?}", solver_options.order);
+                },
+                Action::CycleStrategy => {
+                    solver_options.strategy = solver_options.strategy.next();
+                    message = format!("Strategy: {}", solver_options.strategy);
+                },
+                Action::ToggleRandom => {
+                    solver_options.seed = match solver_options.seed {
+                        Some(_) => None,
+                        None => Some(Rng::from_entropy().next_u64())
+                    };
+                    message = format!("Random order {}", if solver_options.seed.is_some() { "on" } else { "off" });
+                    board.clear_soft();
+                    solving = false;
+                    edited = true;
+                },
+                Action::Minimize => match minimize(&board) {
+                    Some(minimal) => {
+                        board = minimal;
+                        message = format!("{} clues left", board.clues());
+                        solving = false;
+                        edited = true;
+                    },
+                    None => message = "Only boards with a unique solution can be minimized".to_string()
+                },
+                Action::Export => message = board.to_line(),
+                Action::LoadTest => {
+                    board = test_board();
+                    edited = true;
+                },
+                Action::LoadDaily => {
+                    board = daily_puzzle(SystemTime::now());
+                    edited = true;
+                },
+                Action::CycleDifficulty => {
+                    difficulty = match difficulty {
+                        Difficulty::Easy => Difficulty::Medium,
+                        Difficulty::Medium => Difficulty::Hard,
+                        Difficulty::Hard => Difficulty::Expert,
+                        Difficulty::Expert => Difficulty::Easy
+                    };
+                    message = format!("Difficulty: {difficulty}");
+                },
+                Action::Generate => {
+                    if let Some(generation) = &generation {
+                        generation.cancel();
+                    }
+                    generation = Some(Generation::start(difficulty));
+                    solving = false;
+                },
+                Action::Cancel => {
+                    if let Some(generation) = generation.take() {
+                        generation.cancel();
+                    }
+                }
+            }
+        }
+
+        if edited {
+            solver = solver_options.build();
+            last_step = None;
+            log.clear();
+            progress = Progress::new(&board);
+            finished = false;
+            unsolvable = false;
+            solutions = count_solutions(&board, 2);
+        }
+
+        let paused = !solving && !finished && log.position() > 0;
+        let status = match &generation {
+            Some(generation) => format!("Generating {} puzzle, attempt {} {} (Esc to cancel)", generation.difficulty, generation.attempt, generation.spinner()),
+            None if unsolvable => "No solution".to_string(),
+            None if progress.steps > 0 => {
+                format!("{} nodes, {} backtracks{}", progress.nodes, progress.backtracks, if paused { " (paused)" } else { "" })
+            },
+            None if solutions == 0 => "This board has no solution".to_string(),
+            None if solutions > 1 => "This board has several solutions".to_string(),
+            None => String::new()
+        };
+        let screen = Screen {
+            cursor: cursor_index,
+            region: region.map(|region| region.unit(cursor_index)),
+            last_step: last_step.as_ref(),
+            status: &status,
+            message: &message
+        };
+        draw(stdout, &board, &screen)?;
+    }
+    Ok(())
+}
+
+/// Takes one solver step and records it in `progress` and `log`, replaying
+/// the log first if it has been rewound.
+fn advance(solver: &mut dyn Solver, board: &mut Board, progress: &mut Progress, log: &mut SolveLog) -> StepResult {
+    if let Some(step) = log.replay(board) {
+        progress.record(step);
+        return StepResult::Solving(step.clone());
+    }
+
+    let result = solver.step(board);
+    if let StepResult::Solving(step) = &result {
+        progress.record(step);
+        log.push(step.clone());
+    }
+    result
+}
+
+/// Everything drawn besides the digits and the grid.
+struct Screen<'a> {
+    cursor: (i8, i8),
+    region: Option<Unit>,
+    last_step: Option<&'a Step>,
+    /// The solve progress or what is wrong with the board.
+    status: &'a str,
+    /// The answer to the last key that has something to say.
+    message: &'a str
+}
+
+/// Draws the grid as text, two columns per cell with a line between boxes.
+fn draw(stdout: &mut Stdout, board: &Board, screen: &Screen) -> io::Result<()> {
+    let changed: Vec<(usize, usize)> = match screen.last_step {
+        Some(Step::Eliminate { eliminations, .. } | Step::Restore { eliminations }) => eliminations.iter().map(|&(pos, _)| pos).collect(),
+        Some(Step::Place { pos, .. } | Step::Unplace { pos, .. }) => vec![*pos],
+        None => Vec::new()
+    };
+    let region = screen.region.map(|unit| unit.cells().to_vec()).unwrap_or_default();
+
+    queue!(stdout, MoveTo(0, 0))?;
+    for y in 0..9 {
+        if y % 3 == 0 {
+            queue!(stdout, Print("+-------+-------+-------+"), MoveTo(0, (y + y / 3 + 1) as u16))?;
+        }
+        for x in 0..9 {
+            if x % 3 == 0 {
+                queue!(stdout, Print("| "))?;
+            }
+            let pos = (x, y);
+            let background = if screen.cursor == (x as i8, y as i8) {
+                Some(Color::Grey)
+            } else if changed.contains(&pos) {
+                Some(Color::DarkBlue)
+            } else if region.contains(&pos) {
+                Some(Color::DarkGreen)
+            } else {
+                None
+            };
+            if let Some(background) = background {
+                queue!(stdout, SetBackgroundColor(background))?;
+            }
+            match board.get(pos) {
+                Tile::Hard(digit) => queue!(stdout, SetForegroundColor(Color::Yellow), SetAttribute(Attribute::Bold), Print(digit))?,
+                Tile::Soft(digit) => queue!(stdout, SetForegroundColor(Color::Cyan), Print(digit))?,
+                Tile::Empty => queue!(stdout, SetForegroundColor(Color::DarkGrey), Print('.'))?
+            }
+            queue!(stdout, SetAttribute(Attribute::Reset), ResetColor, Print(' '))?;
+        }
+        queue!(stdout, Print('|'), MoveTo(0, (y + y / 3 + 2) as u16))?;
+    }
+    queue!(stdout, Print("+-------+-------+-------+"))?;
+
+    queue!(stdout, MoveTo(0, 14), Clear(ClearType::UntilNewLine), Print(screen.status))?;
+    queue!(stdout, MoveTo(0, 15), Clear(ClearType::UntilNewLine), Print(screen.message))?;
+    queue!(stdout, MoveTo(0, 17), SetForegroundColor(Color::DarkGrey), Print("Space solve, n step, b back, q quit"), ResetColor)?;
+    stdout.flush()
+}
+
+#[derive(Clone, Copy)]
+enum Region {
+    Row,
+    Column,
+    Box
+}
+
+impl Region {
+    fn unit(self, cursor_index: (i8, i8)) -> Unit {
+        let pos = (cursor_index.0 as usize, cursor_index.1 as usize);
+        match self {
+            Region::Row => Unit::Row(pos.1),
+            Region::Column => Unit::Column(pos.0),
+            Region::Box => Unit::Box(box_index(pos))
+        }
+    }
+}
+
+enum Action {
+    Write(u8),
+    Remove,
+    Move(i8, i8),
+    Solve,
+    StepOnce,
+    StepBack,
+    Faster,
+    Slower,
+    CycleRegion,
+    FillRegion,
+    Minimize,
+    CycleDifficulty,
+    Generate,
+    Cancel,
+    ToggleVisual,
+    ToggleOrder,
+    ToggleRandom,
+    CycleStrategy,
+    Export,
+    LoadTest,
+    LoadDaily
+}
+
+/// Waits up to `timeout` for a key, then takes in every key that is waiting.
+fn handle_input(timeout: Duration, running: &mut bool) -> io::Result<Vec<Action>> {
+    let mut actions = Vec::new();
+    let mut timeout = timeout;
+    while event::poll(timeout)? {
+        timeout = Duration::ZERO;
+        if let Event::Key(key) = event::read()?
+            && let Some(action) = handle_key(key, running)
+        {
+            actions.push(action);
+        }
+    }
+    Ok(actions)
+}
+
+fn handle_key(key: KeyEvent, running: &mut bool) -> Option<Action> {
+    // Held keys only repeat movement, as in the window
+    let repeat = match key.kind {
+        KeyEventKind::Press => false,
+        KeyEventKind::Repeat => true,
+        KeyEventKind::Release => return None
+    };
+    let action = match key.code {
+        KeyCode::Right => Action::Move(1, 0),
+        KeyCode::Left => Action::Move(-1, 0),
+        KeyCode::Up => Action::Move(0, -1),
+        KeyCode::Down => Action::Move(0, 1),
+        _ if repeat => return None,
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            *running = false;
+            return None;
+        },
+        KeyCode::Char('q') => {
+            *running = false;
+            return None;
+        },
+        KeyCode::Char(c @ '1'..='9') => Action::Write(c as u8 - b'0'),
+        KeyCode::Backspace | KeyCode::Delete => Action::Remove,
+        KeyCode::Char(' ') => Action::Solve,
+        KeyCode::Char('n') => Action::StepOnce,
+        KeyCode::Char('b') => Action::StepBack,
+        KeyCode::Char('+' | '=') => Action::Faster,
+        KeyCode::Char('-') => Action::Slower,
+        KeyCode::Char('g') => Action::CycleRegion,
+        KeyCode::Char('f') => Action::FillRegion,
+        KeyCode::Char('v') => Action::ToggleVisual,
+        KeyCode::Char('o') => Action::ToggleOrder,
+        KeyCode::Char('r') => Action::ToggleRandom,
+        KeyCode::Char('s') => Action::CycleStrategy,
+        KeyCode::Char('t') => Action::LoadTest,
+        KeyCode::Char('d') => Action::LoadDaily,
+        KeyCode::Char('m') => Action::Minimize,
+        KeyCode::Char('y') => Action::CycleDifficulty,
+        KeyCode::Char('x') => Action::Generate,
+        KeyCode::Char('e') => Action::Export,
+        KeyCode::Esc => Action::Cancel,
+        _ => return None
+    };
+    Some(action)
+}
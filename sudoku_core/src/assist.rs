@@ -0,0 +1,73 @@
+use crate::{
+    backtracker::Backtracker,
+    board::{Board, Tile, Unit},
+    candidates::Candidates,
+    notes::Notes,
+    solver::{Reason, Solver, SolverOptions, Step, StepResult},
+    techniques::{Deduction, Techniques}
+};
+
+/// Fills the empty cells of `unit` whose digits follow from `techniques` alone.
+///
+/// The whole board is solved on a copy for as long as no guess is needed, and
+/// only the placements inside `unit` are written back to `board`, as soft
+/// tiles. Nothing is written if the deductions run into a contradiction
+/// before the unit is full. Returns the placements made, in the order they
+/// were found.
+pub fn fill_unit(board: &mut Board, unit: Unit, techniques: Techniques) -> Vec<Step> {
+    if !board.is_valid() {
+        return Vec::new();
+    }
+
+    let cells = unit.cells();
+    let mut scratch = *board;
+    let mut solver = Backtracker::with_options(SolverOptions { techniques, ..SolverOptions::default() });
+    let mut placed = Vec::new();
+
+    while cells.iter().any(|&pos| scratch.get(pos) == Tile::Empty) {
+        match solver.step(&mut scratch) {
+            StepResult::Solving(step @ Step::Place { reason: Reason::Logic(_), .. }) => {
+                if let Step::Place { pos, .. } = step && cells.contains(&pos) {
+                    placed.push(step);
+                }
+            },
+            StepResult::Solving(Step::Eliminate { .. }) => (),
+            // A guess means logic has run out, and backtracking means it hit a contradiction
+            StepResult::Solving(Step::Place { .. }) | StepResult::Finished => break,
+            StepResult::Solving(Step::Unplace { .. } | Step::Restore { .. }) | StepResult::Unsolvable => {
+                return Vec::new();
+            }
+        }
+    }
+
+    for step in &placed {
+        step.apply(board);
+    }
+    placed
+}
+
+/// The next move logic alone finds on `board`, for a player who is stuck.
+///
+/// Cells with notes are taken to hold only the noted candidates, so
+/// eliminations the player has already made aren't suggested again. Returns
+/// `None` if no technique applies, or if the digits and notes contradict
+/// each other.
+pub fn hint(board: &Board, notes: &Notes, techniques: Techniques) -> Option<Step> {
+    if !board.is_valid() {
+        return None;
+    }
+
+    let mut candidates = Candidates::from_board(board);
+    for y in 0..9 {
+        for x in 0..9 {
+            if notes.get((x, y)) != 0 {
+                candidates.remove((x, y), !notes.get((x, y)));
+            }
+        }
+    }
+    match techniques.deduce(board, &candidates)? {
+        Deduction::Place { pos, digit, technique } => Some(Step::Place { pos, digit, reason: Reason::Logic(technique) }),
+        Deduction::Eliminate { eliminations, technique } => Some(Step::Eliminate { eliminations, technique }),
+        Deduction::Contradiction => None
+    }
+}
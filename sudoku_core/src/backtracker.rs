@@ -0,0 +1,145 @@
+use crate::{
+    board::{get_pos, Board, Tile},
+    candidates::{digits, Candidates},
+    rng::Rng,
+    solver::{CellOrder, Reason, Solver, SolverOptions, Step, StepResult},
+    techniques::Deduction
+};
+
+#[derive(Clone, Debug)]
+enum Entry {
+    /// For guesses, `untried` holds the candidates left to try on backtracking.
+    Place { pos: (usize, usize), digit: u8, reason: Reason, untried: u16 },
+    Eliminate(Vec<((usize, usize), u16)>)
+}
+
+/// A step-by-step solver which applies logical techniques before falling
+/// back to backtracking.
+///
+/// Every digit the solver writes and every candidate it removes is recorded on
+/// a trail so it can be undone when a guess turns out to be wrong.
+#[derive(Default)]
+pub struct Backtracker {
+    options: SolverOptions,
+    trail: Vec<Entry>,
+    eliminated: [[u16; 9]; 9],
+    backtracking: bool,
+    /// A guess which was taken back and should be retried with one of the
+    /// untried candidates.
+    retry: Option<((usize, usize), u16)>,
+    rng: Option<Rng>
+}
+
+impl Backtracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(options: SolverOptions) -> Self {
+        Self { options, rng: options.seed.map(Rng::new), ..Self::default() }
+    }
+
+    pub fn options(&self) -> SolverOptions {
+        self.options
+    }
+
+    fn place(&mut self, board: &mut Board, pos: (usize, usize), digit: u8, reason: Reason, untried: u16) -> Step {
+        board.set(pos, Tile::Soft(digit));
+        self.trail.push(Entry::Place { pos, digit, reason, untried });
+        Step::Place { pos, digit, reason }
+    }
+
+    /// Places one of the `options` at `pos`: the lowest, or a random one if
+    /// the solver was seeded.
+    fn guess(&mut self, board: &mut Board, pos: (usize, usize), options: u16) -> Step {
+        let digit = match &mut self.rng {
+            Some(rng) => rng.pick_digit(options),
+            None => digits(options).next()
+        };
+        let digit = digit.expect("guesses are only made with candidates left");
+        self.place(board, pos, digit, Reason::Guess, options & !(1 << digit))
+    }
+
+    /// Undoes the most recent trail entry. If it was a guess with untried
+    /// candidates left, backtracking ends and the next step retries it.
+    ///
+    /// Returns `None` if there is nothing left to undo.
+    fn backtrack(&mut self, board: &mut Board) -> Option<Step> {
+        let last = self.trail.pop()?;
+
+        Some(match last {
+            Entry::Eliminate(eliminations) => {
+                for &(pos, mask) in &eliminations {
+                    self.eliminated[pos.1][pos.0] &= !mask;
+                }
+                Step::Restore { eliminations }
+            },
+            Entry::Place { pos, digit, reason, untried } => {
+                board.set(pos, Tile::Empty);
+                if reason == Reason::Guess && untried != 0 {
+                    self.retry = Some((pos, untried));
+                    self.backtracking = false;
+                }
+                Step::Unplace { pos, digit }
+            }
+        })
+    }
+
+    /// The empty cell to guess in next, according to the cell order.
+    fn next_cell(&self, board: &Board, candidates: &Candidates) -> Option<(usize, usize)> {
+        let mut empty = (0..81).map(get_pos).filter(|pos| board.get(*pos) == Tile::Empty);
+        match self.options.order {
+            CellOrder::Raster => empty.next(),
+            CellOrder::MostConstrained => empty.min_by_key(|pos| candidates.get(*pos).count_ones())
+        }
+    }
+}
+
+impl Solver for Backtracker {
+    /// The candidates left on `board` after the solver's eliminations.
+    fn candidates(&self, board: &Board) -> Candidates {
+        let mut candidates = Candidates::from_board(board);
+        for idx in 0..81 {
+            let pos = get_pos(idx);
+            candidates.remove(pos, self.eliminated[pos.1][pos.0]);
+        }
+        candidates
+    }
+
+    /// Advances the solver by one placement, elimination or undo.
+    fn step(&mut self, board: &mut Board) -> StepResult {
+        if let Some((pos, untried)) = self.retry.take() {
+            return StepResult::Solving(self.guess(board, pos, untried));
+        }
+
+        if self.backtracking {
+            return self.backtrack(board).map_or(StepResult::Unsolvable, StepResult::Solving);
+        }
+
+        let candidates = self.candidates(board);
+        let step = match self.options.techniques.deduce(board, &candidates) {
+            Some(Deduction::Contradiction) => {
+                self.backtracking = true;
+                match self.backtrack(board) {
+                    Some(step) => step,
+                    None => return StepResult::Unsolvable
+                }
+            },
+            Some(Deduction::Place { pos, digit, technique }) => {
+                self.place(board, pos, digit, Reason::Logic(technique), 0)
+            },
+            Some(Deduction::Eliminate { eliminations, technique }) => {
+                for &(pos, mask) in &eliminations {
+                    self.eliminated[pos.1][pos.0] |= mask;
+                }
+                self.trail.push(Entry::Eliminate(eliminations.clone()));
+                Step::Eliminate { eliminations, technique }
+            },
+            None => match self.next_cell(board, &candidates) {
+                Some(pos) => self.guess(board, pos, candidates.get(pos)),
+                None => return StepResult::Finished
+            }
+        };
+        StepResult::Solving(step)
+    }
+}
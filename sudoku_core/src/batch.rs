@@ -0,0 +1,45 @@
+use std::time::{Duration, Instant};
+
+use crate::{board::Board, search::find_solution};
+
+/// Why a board in a batch did or didn't get solved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveStatus {
+    Solved,
+    /// The board follows the rules but can't be completed.
+    Unsolvable,
+    /// The board already breaks the rules.
+    Invalid
+}
+
+/// The result of solving one board in a batch.
+#[derive(Clone, Copy, Debug)]
+pub struct SolveResult {
+    pub status: SolveStatus,
+    /// The first solution found, if the board was solved.
+    pub solution: Option<Board>,
+    /// How long this board took on its own.
+    pub time: Duration
+}
+
+/// Solves every board in `boards` one after another, timing each one.
+///
+/// Boards are solved on the calling thread so the timings can be compared
+/// with each other; use [`crate::solve_many`] when only the solutions matter.
+/// Results are in the same order as the boards.
+pub fn solve_all(boards: impl Iterator<Item = Board>) -> Vec<SolveResult> {
+    boards.map(|board| solve_timed(&board)).collect()
+}
+
+fn solve_timed(board: &Board) -> SolveResult {
+    let start = Instant::now();
+    let (status, solution) = if !board.is_valid() {
+        (SolveStatus::Invalid, None)
+    } else {
+        match find_solution(board) {
+            Some(solution) => (SolveStatus::Solved, Some(solution)),
+            None => (SolveStatus::Unsolvable, None)
+        }
+    };
+    SolveResult { status, solution, time: start.elapsed() }
+}
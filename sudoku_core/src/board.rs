@@ -0,0 +1,380 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::candidates::ALL_DIGITS;
+
+/// The contents of a single cell on the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tile {
+    /// A given digit entered by the user.
+    Hard(u8),
+    /// A digit placed by the solver.
+    Soft(u8),
+    Empty
+}
+
+impl Tile {
+    /// The digit in this tile, if any.
+    pub fn digit(self) -> Option<u8> {
+        match self {
+            Tile::Hard(num) | Tile::Soft(num) => Some(num),
+            Tile::Empty => None
+        }
+    }
+}
+
+/// A row, column or 3x3 section of the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Row(usize),
+    Column(usize),
+    Box(usize)
+}
+
+impl Unit {
+    /// All 27 units of the board: rows, then columns, then boxes.
+    pub fn all() -> impl Iterator<Item = Unit> {
+        (0..9).map(Unit::Row)
+            .chain((0..9).map(Unit::Column))
+            .chain((0..9).map(Unit::Box))
+    }
+
+    /// The three units containing `pos`.
+    pub fn containing(pos: (usize, usize)) -> [Unit; 3] {
+        [Unit::Row(pos.1), Unit::Column(pos.0), Unit::Box(box_index(pos))]
+    }
+
+    /// The `(x, y)` positions covered by this unit.
+    pub fn cells(self) -> [(usize, usize); 9] {
+        std::array::from_fn(|i| match self {
+            Unit::Row(y) => (i, y),
+            Unit::Column(x) => (x, i),
+            Unit::Box(b) => ((b % 3) * 3 + i % 3, (b / 3) * 3 + i / 3)
+        })
+    }
+
+    /// A unique index in 0..27, in the same order as [`Unit::all`].
+    fn index(self) -> usize {
+        match self {
+            Unit::Row(y) => y,
+            Unit::Column(x) => 9 + x,
+            Unit::Box(b) => 18 + b
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unit::Row(y) => write!(f, "row {}", y + 1),
+            Unit::Column(x) => write!(f, "column {}", x + 1),
+            Unit::Box(b) => write!(f, "box {}", b + 1)
+        }
+    }
+}
+
+/// The index of the 3x3 box containing `pos`.
+pub fn box_index(pos: (usize, usize)) -> usize {
+    (pos.1 / 3) * 3 + pos.0 / 3
+}
+
+/// The conventional `r1c1`-style name of the cell at `pos`, counting from 1.
+pub fn cell_name(pos: (usize, usize)) -> String {
+    format!("r{}c{}", pos.1 + 1, pos.0 + 1)
+}
+
+/// Converts a raster index (0..81) into an `(x, y)` position.
+pub fn get_pos(idx: usize) -> (usize, usize) {
+    (idx % 9, idx / 9)
+}
+
+/// A 9x9 sudoku board, addressed by `(x, y)` positions.
+///
+/// Alongside the tiles, the board keeps a count of every digit in every row,
+/// column and box, and the candidates of every empty cell. Both are updated
+/// incrementally on each [`Board::set`], so looking up candidates and checking
+/// validity is constant time, and the solver and frontends share one source
+/// of truth for them.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Board {
+    tiles: [[Tile; 9]; 9],
+    /// How often each digit occurs in each unit, indexed by [`Unit::index`].
+    counts: [[u8; 10]; 27],
+    /// A bitmask per unit with bit `n` set if digit `n` occurs in it.
+    masks: [u16; 27],
+    /// The number of (unit, digit) pairs where the digit occurs more than once.
+    duplicates: usize,
+    /// The digits not taken by any peer of each empty cell, zero for filled cells.
+    candidates: [[u16; 9]; 9]
+}
+
+impl Board {
+    /// Creates a board with every tile empty.
+    pub const fn new() -> Self {
+        Self {
+            tiles: [[Tile::Empty; 9]; 9],
+            counts: [[0; 10]; 27],
+            masks: [0; 27],
+            duplicates: 0,
+            candidates: [[ALL_DIGITS; 9]; 9]
+        }
+    }
+
+    /// Creates a board from rows of tiles, indexed as `tiles[y][x]`.
+    pub fn from_tiles(tiles: [[Tile; 9]; 9]) -> Self {
+        let mut board = Self::new();
+        for idx in 0..81 {
+            let pos = get_pos(idx);
+            board.set(pos, tiles[pos.1][pos.0]);
+        }
+        board
+    }
+
+    /// The rows of tiles, indexed as `tiles[y][x]`.
+    pub fn tiles(&self) -> &[[Tile; 9]; 9] {
+        &self.tiles
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Tile {
+        self.tiles[pos.1][pos.0]
+    }
+
+    pub fn set(&mut self, pos: (usize, usize), tile: Tile) {
+        if let Some(num) = self.get(pos).digit() {
+            for unit in Unit::containing(pos) {
+                self.uncount(unit.index(), num);
+            }
+        }
+        if let Some(num) = tile.digit() {
+            for unit in Unit::containing(pos) {
+                self.count(unit.index(), num);
+            }
+        }
+        self.tiles[pos.1][pos.0] = tile;
+
+        // Only the cell itself and its peers can see a different set of digits
+        for unit in Unit::containing(pos) {
+            for peer in unit.cells() {
+                self.candidates[peer.1][peer.0] = match self.get(peer) {
+                    Tile::Empty => ALL_DIGITS & !self.taken(peer),
+                    _ => 0
+                };
+            }
+        }
+    }
+
+    fn count(&mut self, unit: usize, num: u8) {
+        let count = &mut self.counts[unit][num as usize];
+        *count += 1;
+        match *count {
+            1 => self.masks[unit] |= 1 << num,
+            2 => self.duplicates += 1,
+            _ => ()
+        }
+    }
+
+    fn uncount(&mut self, unit: usize, num: u8) {
+        let count = &mut self.counts[unit][num as usize];
+        *count -= 1;
+        match *count {
+            0 => self.masks[unit] &= !(1 << num),
+            1 => self.duplicates -= 1,
+            _ => ()
+        }
+    }
+
+    /// A bitmask of the digits present in `unit`, with bit `n` set for digit `n`.
+    pub fn placed(&self, unit: Unit) -> u16 {
+        self.masks[unit.index()]
+    }
+
+    /// A bitmask of every digit already present in the row, column and section of `pos`.
+    pub fn taken(&self, pos: (usize, usize)) -> u16 {
+        Unit::containing(pos).iter().fold(0, |mask, &unit| mask | self.placed(unit))
+    }
+
+    /// The candidate mask of the cell at `pos`: every digit not taken by a
+    /// peer if the cell is empty, otherwise zero.
+    pub fn candidates(&self, pos: (usize, usize)) -> u16 {
+        self.candidates[pos.1][pos.0]
+    }
+
+    /// The candidate masks of every cell, indexed as `grid[y][x]`.
+    pub(crate) fn candidate_grid(&self) -> [[u16; 9]; 9] {
+        self.candidates
+    }
+
+    /// Returns true if no row, column or section contains the same digit twice.
+    pub fn is_valid(&self) -> bool {
+        self.duplicates == 0
+    }
+
+    /// The cells whose digit appears again in their row, column or section.
+    pub fn conflicts(&self) -> Vec<(usize, usize)> {
+        (0..81).map(get_pos).filter(|&pos| {
+            self.get(pos).digit().is_some_and(|digit| {
+                Unit::containing(pos).iter().any(|unit| {
+                    unit.cells().iter().any(|&peer| peer != pos && self.get(peer).digit() == Some(digit))
+                })
+            })
+        }).collect()
+    }
+
+    /// Empties every `Soft` tile, keeping only the givens.
+    pub fn clear_soft(&mut self) {
+        for idx in 0..81 {
+            let pos = get_pos(idx);
+            if let Tile::Soft(_) = self.get(pos) {
+                self.set(pos, Tile::Empty);
+            }
+        }
+    }
+
+    /// Returns true if every tile is filled.
+    pub fn is_full(&self) -> bool {
+        self.tiles.iter().flatten().all(|tile| *tile != Tile::Empty)
+    }
+
+    /// The board as one line of 81 characters in reading order, with `.` for
+    /// empty tiles.
+    pub fn to_line(&self) -> String {
+        self.tiles.iter().flatten().map(|tile| match tile.digit() {
+            Some(digit) => char::from(b'0' + digit),
+            None => '.'
+        }).collect()
+    }
+
+    /// The number of tiles left empty.
+    pub fn empty_cells(&self) -> usize {
+        self.tiles.iter().flatten().filter(|tile| **tile == Tile::Empty).count()
+    }
+
+    /// The number of givens, that is `Hard` tiles, on the board.
+    pub fn clues(&self) -> usize {
+        self.tiles.iter().flatten().filter(|tile| matches!(tile, Tile::Hard(_))).count()
+    }
+}
+
+/// Why a board couldn't be parsed from text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseBoardError {
+    /// A character that is neither a digit, `.`, whitespace nor a grid line.
+    InvalidCharacter(char),
+    /// The text had this many cells instead of 81.
+    WrongLength(usize)
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBoardError::InvalidCharacter(c) => write!(f, "unexpected character {c:?} in board"),
+            ParseBoardError::WrongLength(len) => write!(f, "expected 81 cells but found {len}")
+        }
+    }
+}
+
+impl Error for ParseBoardError {}
+
+impl FromStr for Board {
+    type Err = ParseBoardError;
+
+    /// Parses the 81 cells of a board in reading order, as written by
+    /// [`Board::to_line`]. Digits become givens and `.` or `0` an empty tile.
+    /// Whitespace and the `|`, `-` and `+` of drawn grid lines are skipped,
+    /// so the cells may also be laid out as a grid over several lines.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut board = Board::new();
+        let mut len = 0;
+        for c in s.chars().filter(|c| !c.is_whitespace() && !matches!(c, '|' | '-' | '+')) {
+            let tile = match c {
+                '.' | '0' => Tile::Empty,
+                '1'..='9' => Tile::Hard(c as u8 - b'0'),
+                _ => return Err(ParseBoardError::InvalidCharacter(c))
+            };
+            if len < 81 {
+                board.set(get_pos(len), tile);
+            }
+            len += 1;
+        }
+
+        if len != 81 {
+            return Err(ParseBoardError::WrongLength(len));
+        }
+        Ok(board)
+    }
+}
+
+/// Boards are stored as their rows of tiles; everything else is worked out
+/// again when loading.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Board {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.tiles.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Board {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tiles = <[[Tile; 9]; 9]>::deserialize(deserializer)?;
+        if let Some(digit) = tiles.iter().flatten().filter_map(|tile| tile.digit()).find(|digit| !(1..=9).contains(digit)) {
+            return Err(serde::de::Error::custom(format!("{digit} is not a sudoku digit")));
+        }
+        Ok(Board::from_tiles(tiles))
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.tiles.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// AI Escargot, with 23 givens.
+    const ESCARGOT: &str = "1....7.9..3..2...8..96..5....53..9...1..8...26....4...3......1..4......7..7...3..";
+
+    #[test]
+    fn lines_round_trip() {
+        let board: Board = ESCARGOT.parse().unwrap();
+        assert_eq!(board.to_line(), ESCARGOT);
+        assert_eq!(board.clues(), 23);
+        assert_eq!(board.get((0, 0)), Tile::Hard(1));
+        assert_eq!(board.get((1, 0)), Tile::Empty);
+    }
+
+    #[test]
+    fn parses_grids_and_zeros() {
+        let grid = "
+            1 . . | . . 7 | . 9 .
+            . 3 . | . 2 . | . . 8
+            . . 9 | 6 . . | 5 . .
+            ------+-------+------
+            . . 5 | 3 . . | 9 . .
+            . 1 . | . 8 . | . . 2
+            6 . . | . . 4 | . . .
+            ------+-------+------
+            3 . . | . . . | . 1 .
+            . 4 . | . . . | . . 7
+            . . 7 | . . . | 3 . .
+        ";
+        assert_eq!(grid.parse::<Board>().unwrap().to_line(), ESCARGOT);
+        assert_eq!(ESCARGOT.replace('.', "0").parse::<Board>().unwrap().to_line(), ESCARGOT);
+    }
+
+    #[test]
+    fn rejects_bad_boards() {
+        assert_eq!(ESCARGOT[1..].parse::<Board>(), Err(ParseBoardError::WrongLength(80)));
+        assert_eq!(format!("{ESCARGOT}.").parse::<Board>(), Err(ParseBoardError::WrongLength(82)));
+        assert_eq!(ESCARGOT.replacen('.', "x", 1).parse::<Board>(), Err(ParseBoardError::InvalidCharacter('x')));
+    }
+}
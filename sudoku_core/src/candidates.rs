@@ -0,0 +1,40 @@
+use crate::board::Board;
+
+/// All nine digits as a candidate mask, with bit `n` set for digit `n`.
+pub const ALL_DIGITS: u16 = 0b11_1111_1110;
+
+/// Iterates over the digits set in a candidate mask, in ascending order.
+pub fn digits(mask: u16) -> impl Iterator<Item = u8> {
+    (1..10).filter(move |n| mask & (1 << n) != 0)
+}
+
+/// A snapshot of the candidate digits of every cell on the board.
+///
+/// Filled cells have no candidates. Empty cells start out with every digit
+/// not already taken by a peer, and techniques may remove more without
+/// affecting the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Candidates {
+    cells: [[u16; 9]; 9]
+}
+
+impl Candidates {
+    /// Takes the candidates the board currently tracks for every cell.
+    pub fn from_board(board: &Board) -> Self {
+        Self { cells: board.candidate_grid() }
+    }
+
+    /// The candidate mask of the cell at `pos`.
+    pub fn get(&self, pos: (usize, usize)) -> u16 {
+        self.cells[pos.1][pos.0]
+    }
+
+    pub fn contains(&self, pos: (usize, usize), digit: u8) -> bool {
+        self.get(pos) & (1 << digit) != 0
+    }
+
+    /// Removes every digit in `mask` from the cell at `pos`.
+    pub fn remove(&mut self, pos: (usize, usize), mask: u16) {
+        self.cells[pos.1][pos.0] &= !mask;
+    }
+}
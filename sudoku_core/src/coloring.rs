@@ -0,0 +1,31 @@
+/// How many colors cells can be marked with.
+pub const COLOR_COUNT: u8 = 4;
+
+/// The colors the player has marked cells with, kept apart from the board like
+/// the notes, for following chains or coloring candidates by hand. Each cell
+/// holds 0 for no color, or 1 to [`COLOR_COUNT`] for one of the colors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coloring {
+    cells: [[u8; 9]; 9]
+}
+
+impl Coloring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The color of the cell at `pos`.
+    pub fn get(&self, pos: (usize, usize)) -> u8 {
+        self.cells[pos.1][pos.0]
+    }
+
+    pub fn set(&mut self, pos: (usize, usize), color: u8) {
+        self.cells[pos.1][pos.0] = color % (COLOR_COUNT + 1);
+    }
+
+    /// The color after the one at `pos`, going back to none after the last.
+    pub fn next(&self, pos: (usize, usize)) -> u8 {
+        (self.get(pos) + 1) % (COLOR_COUNT + 1)
+    }
+}
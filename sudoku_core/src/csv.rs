@@ -0,0 +1,111 @@
+use crate::{
+    board::{Board, ParseBoardError},
+    sdk::ParseCollectionError
+};
+
+/// A puzzle read from one row of a CSV or TSV file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsvPuzzle {
+    pub board: Board,
+    pub label: Option<String>
+}
+
+/// Parses a CSV file with one puzzle per row, or a TSV file if the text has
+/// any tabs in it.
+///
+/// A puzzle is either one field of 81 cells, nine fields of nine cells for the
+/// rows, or 81 fields of one cell each, where an empty field is an empty cell.
+/// Cells are digits with `.` or `0` for empty ones. The first other field that
+/// isn't empty becomes the label, so the label column may come before or after
+/// the puzzle; further columns with whole puzzles, such as the solutions, are
+/// ignored. A first row without a puzzle is skipped as a header, and so are
+/// blank lines.
+pub fn parse_csv(text: &str) -> Result<Vec<CsvPuzzle>, ParseCollectionError> {
+    let delimiter = if text.contains('\t') { '\t' } else { ',' };
+    let mut puzzles = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let fields = split_fields(line, delimiter);
+        match parse_row(&fields) {
+            Ok(puzzle) => puzzles.push(puzzle),
+            Err(_) if idx == 0 && !fields.iter().any(|field| field.len() >= 9 && is_cells(field, field.len())) => (),
+            Err(error) => return Err(ParseCollectionError { line: idx + 1, error })
+        }
+    }
+    Ok(puzzles)
+}
+
+/// Splits a row into trimmed fields, keeping delimiters inside double quotes.
+fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // A doubled quote inside quotes stands for one quote
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c)
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Returns true if `field` is exactly `len` cells.
+fn is_cells(field: &str, len: usize) -> bool {
+    field.len() == len && field.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+fn parse_row(fields: &[String]) -> Result<CsvPuzzle, ParseBoardError> {
+    // Try the layouts from the most to the least specific, remembering which
+    // fields make up the puzzle
+    let used: Vec<usize> = if let Some(idx) = fields.iter().position(|field| is_cells(field, 81)) {
+        vec![idx]
+    } else if fields.iter().filter(|field| is_cells(field, 9)).count() >= 9 {
+        fields.iter().enumerate().filter(|(_, field)| is_cells(field, 9)).map(|(idx, _)| idx).take(9).collect()
+    } else {
+        fields.iter().enumerate().filter(|(_, field)| field.is_empty() || is_cells(field, 1)).map(|(idx, _)| idx).take(81).collect()
+    };
+
+    let text: String = used.iter().map(|&idx| if fields[idx].is_empty() { "." } else { fields[idx].as_str() }).collect();
+    let board = text.parse()?;
+    let label = fields.iter()
+        .enumerate()
+        .find(|&(idx, field)| !used.contains(&idx) && !field.is_empty() && !is_cells(field, 81))
+        .map(|(_, field)| field.clone());
+    Ok(CsvPuzzle { board, label })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ESCARGOT: &str = "1....7.9..3..2...8..96..5....53..9...1..8...26....4...3......1..4......7..7...3..";
+
+    #[test]
+    fn reads_labels_and_skips_the_header() {
+        let text = format!("name,puzzle\nAI Escargot,{ESCARGOT}\n\n,{}\n", ".".repeat(81));
+        let puzzles = parse_csv(&text).unwrap();
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(puzzles[0].board.to_line(), ESCARGOT);
+        assert_eq!(puzzles[0].label.as_deref(), Some("AI Escargot"));
+        assert_eq!(puzzles[1], CsvPuzzle { board: Board::new(), label: None });
+    }
+
+    #[test]
+    fn reads_rows_as_fields() {
+        let rows: Vec<&str> = (0..9).map(|row| &ESCARGOT[row * 9..row * 9 + 9]).collect();
+        let puzzles = parse_csv(&rows.join("\t")).unwrap();
+        assert_eq!(puzzles.len(), 1);
+        assert_eq!(puzzles[0].board.to_line(), ESCARGOT);
+    }
+}
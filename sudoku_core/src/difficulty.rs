@@ -0,0 +1,121 @@
+use std::fmt;
+
+use crate::{
+    backtracker::Backtracker,
+    board::Board,
+    search::count_solutions,
+    solver::{Reason, Solver, SolverOptions, Step, StepResult},
+    techniques::{Technique, Techniques}
+};
+
+/// How hard a puzzle is, judged by the hardest technique needed to solve it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// Naked and hidden singles are enough.
+    Easy,
+    /// Needs locked candidates.
+    Medium,
+    /// Needs naked or hidden subsets.
+    Hard,
+    /// Can't be solved without guessing.
+    Expert
+}
+
+impl Difficulty {
+    /// The difficulty a technique adds to a puzzle that needs it.
+    pub fn of_technique(technique: Technique) -> Self {
+        match technique {
+            Technique::NakedSingle | Technique::HiddenSingle(_) => Difficulty::Easy,
+            Technique::Pointing { .. } | Technique::BoxLineReduction { .. } => Difficulty::Medium,
+            Technique::NakedSubset { .. } | Technique::HiddenSubset { .. } => Difficulty::Hard
+        }
+    }
+
+    /// Looks up a difficulty by the name it is displayed with, such as `hard`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Expert]
+            .into_iter()
+            .find(|difficulty| difficulty.to_string() == name)
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Medium => write!(f, "medium"),
+            Difficulty::Hard => write!(f, "hard"),
+            Difficulty::Expert => write!(f, "expert")
+        }
+    }
+}
+
+/// Rates `board` by solving it with every technique and no guessing.
+///
+/// Techniques are always tried from easiest to hardest, so the hardest one
+/// used is the one the puzzle really needs. Returns `None` if the board
+/// doesn't have exactly one solution.
+pub fn rate(board: &Board) -> Option<Difficulty> {
+    if count_solutions(board, 2) != 1 {
+        return None;
+    }
+
+    let mut board = *board;
+    let mut solver = Backtracker::with_options(SolverOptions { techniques: Techniques::all(), ..SolverOptions::default() });
+    let mut difficulty = Difficulty::Easy;
+    loop {
+        let technique = match solver.step(&mut board) {
+            StepResult::Solving(Step::Place { reason: Reason::Logic(technique), .. } | Step::Eliminate { technique, .. }) => technique,
+            StepResult::Solving(_) => return Some(Difficulty::Expert),
+            StepResult::Finished | StepResult::Unsolvable => return Some(difficulty)
+        };
+        difficulty = difficulty.max(Difficulty::of_technique(technique));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Tile;
+
+    /// Puzzles from the window's built-in library, each with the difficulty
+    /// of the shelf it is on.
+    const RATED: [(&str, Difficulty); 11] = [
+        ("8..9.3....39..8.5...........7..6.....5.4..91..1..2.54..4......5.....1...2.5..4.3.", Difficulty::Easy),
+        (".4.....82.718...9........6..9.......6.5.3.21....4....3..85.....3....7...9.....14.", Difficulty::Easy),
+        ("..5.93.1..61.7.....9.1....8.......876..91..........2..43....7...5.2.....98...73..", Difficulty::Medium),
+        ("6.5....7..1....8.4.9...12....4.23......8.........14.....31.869.....7..8.2...4.5..", Difficulty::Medium),
+        ("4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......", Difficulty::Medium),
+        ("5.3.......8..7....2....8.64..6.5.......4.......4...3.89..1..7.23....2..9..8..9...", Difficulty::Hard),
+        ("..7..38...4.18.29....9........4.81....6.......1.7....58.3.....6......7...2....4.1", Difficulty::Hard),
+        ("..234.....7...8.398......52..1.....46.........8..5..7...9.6.7.....7.1.6....8.9...", Difficulty::Expert),
+        (".......7..1.6.7..3.9.4.32.....9..1.75.8.4..9....25......91...3..4.3..5........4..", Difficulty::Expert),
+        ("1....7.9..3..2...8..96..5....53..9...1..8...26....4...3......1..4......7..7...3..", Difficulty::Expert),
+        ("1.......2.9.4...5...6...7...5.9.3.......7.......85..4.7.....6...3...9.8...2.....1", Difficulty::Expert)
+    ];
+
+    #[test]
+    fn rates_the_library() {
+        for (line, difficulty) in RATED {
+            let board: Board = line.parse().unwrap();
+            assert_eq!(count_solutions(&board, 2), 1, "{line}");
+            assert_eq!(rate(&board), Some(difficulty), "{line}");
+        }
+    }
+
+    #[test]
+    fn puzzles_without_one_solution_have_no_rating() {
+        assert_eq!(rate(&Board::new()), None);
+        let mut board: Board = RATED[0].0.parse().unwrap();
+        board.set((1, 0), Tile::Hard(8));
+        assert_eq!(rate(&board), None);
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Expert] {
+            assert_eq!(Difficulty::from_name(&difficulty.to_string()), Some(difficulty));
+        }
+        assert_eq!(Difficulty::from_name("impossible"), None);
+    }
+}
@@ -0,0 +1,281 @@
+use crate::{
+    board::{get_pos, Board, Tile, Unit},
+    solver::{Reason, Solver, Step, StepResult},
+    techniques::Technique
+};
+
+/// Columns of the exact cover matrix: one per cell, then one per
+/// (row, digit), (column, digit) and (box, digit) pair.
+const COLUMNS: usize = 324;
+/// Rows of the exact cover matrix: one per (cell, digit) pair.
+const ROWS: usize = 729;
+const ROOT: usize = 0;
+
+/// The sparse exact cover matrix as circular doubly linked lists.
+///
+/// Node 0 is the root, nodes 1..=324 are the column headers and the rest are
+/// the four nodes of every matrix row.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The column header of every node.
+    column: Vec<usize>,
+    /// The matrix row of every node, unused for headers.
+    row: Vec<usize>,
+    /// The number of nodes in each column, indexed by header.
+    size: Vec<usize>,
+    /// The first node of every matrix row.
+    row_start: Vec<usize>
+}
+
+impl Links {
+    fn new() -> Self {
+        let nodes = 1 + COLUMNS + ROWS * 4;
+        let mut links = Links {
+            left: vec![0; nodes],
+            right: vec![0; nodes],
+            up: (0..nodes).collect(),
+            down: (0..nodes).collect(),
+            column: (0..nodes).collect(),
+            row: vec![0; nodes],
+            size: vec![0; 1 + COLUMNS],
+            row_start: vec![0; ROWS]
+        };
+
+        for header in 0..=COLUMNS {
+            links.left[header] = if header == 0 { COLUMNS } else { header - 1 };
+            links.right[header] = if header == COLUMNS { 0 } else { header + 1 };
+        }
+
+        for row in 0..ROWS {
+            let first = 1 + COLUMNS + row * 4;
+            links.row_start[row] = first;
+            for (i, column) in row_columns(row).into_iter().enumerate() {
+                let node = first + i;
+                let header = column + 1;
+                links.left[node] = if i == 0 { first + 3 } else { node - 1 };
+                links.right[node] = if i == 3 { first } else { node + 1 };
+                links.row[node] = row;
+                links.column[node] = header;
+
+                // Append to the bottom of the column
+                let last = links.up[header];
+                links.up[node] = last;
+                links.down[node] = header;
+                links.down[last] = node;
+                links.up[header] = node;
+                links.size[header] += 1;
+            }
+        }
+
+        links
+    }
+
+    fn cover(&mut self, header: usize) {
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = right;
+        self.left[right] = left;
+
+        let mut i = self.down[header];
+        while i != header {
+            let mut j = self.right[i];
+            while j != i {
+                let (up, down) = (self.up[j], self.down[j]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut i = self.up[header];
+        while i != header {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.column[j]] += 1;
+                let (up, down) = (self.up[j], self.down[j]);
+                self.down[up] = j;
+                self.up[down] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = header;
+        self.left[right] = header;
+    }
+
+    /// The uncovered column with the fewest rows, or `None` if every column is covered.
+    fn smallest_column(&self) -> Option<usize> {
+        let mut best = None;
+        let mut header = self.right[ROOT];
+        while header != ROOT {
+            if best.is_none_or(|best| self.size[header] < self.size[best]) {
+                best = Some(header);
+            }
+            header = self.right[header];
+        }
+        best
+    }
+}
+
+/// The four matrix columns a (cell, digit) row covers.
+fn row_columns(row: usize) -> [usize; 4] {
+    let (pos, digit) = row_cell(row);
+    let d = digit as usize - 1;
+    let b = crate::board::box_index(pos);
+    [pos.1 * 9 + pos.0, 81 + pos.1 * 9 + d, 162 + pos.0 * 9 + d, 243 + b * 9 + d]
+}
+
+/// The cell and digit of a matrix row.
+fn row_cell(row: usize) -> ((usize, usize), u8) {
+    (get_pos(row / 9), (row % 9) as u8 + 1)
+}
+
+/// The technique that explains a forced choice in the column with `header`.
+fn forced_technique(header: usize) -> Technique {
+    let column = header - 1;
+    let unit = (column % 81) / 9;
+    match column / 81 {
+        0 => Technique::NakedSingle,
+        1 => Technique::HiddenSingle(Unit::Row(unit)),
+        2 => Technique::HiddenSingle(Unit::Column(unit)),
+        _ => Technique::HiddenSingle(Unit::Box(unit))
+    }
+}
+
+/// A step-by-step solver using Knuth's Algorithm X with dancing links.
+///
+/// Choices are made in the constraint with the fewest options, so whenever a
+/// choice is forced it is reported as the matching single rather than a guess.
+#[derive(Default)]
+pub struct DlxSolver {
+    /// Built from the board on the first step.
+    links: Option<Links>,
+    /// The column and row node of every choice made so far.
+    stack: Vec<(usize, usize)>,
+    backtracking: bool,
+    /// A choice which was taken back and should be retried with the next row of its column.
+    retry: Option<(usize, usize)>,
+    unsolvable: bool
+}
+
+impl DlxSolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the matrix and removes everything the filled tiles rule out.
+    fn init(&mut self, board: &Board) -> Links {
+        let mut links = Links::new();
+        if !board.is_valid() {
+            self.unsolvable = true;
+            return links;
+        }
+
+        for idx in 0..81 {
+            let pos = get_pos(idx);
+            if let Some(digit) = board.get(pos).digit() {
+                let first = links.row_start[idx * 9 + digit as usize - 1];
+                let mut node = first;
+                loop {
+                    links.cover(links.column[node]);
+                    node = links.right[node];
+                    if node == first {
+                        break;
+                    }
+                }
+            }
+        }
+        links
+    }
+
+    /// Takes the matrix row at `node`, whose column is already covered.
+    fn select(links: &mut Links, stack: &mut Vec<(usize, usize)>, board: &mut Board, header: usize, node: usize, reason: Reason) -> Step {
+        let mut j = links.right[node];
+        while j != node {
+            links.cover(links.column[j]);
+            j = links.right[j];
+        }
+        stack.push((header, node));
+
+        let (pos, digit) = row_cell(links.row[node]);
+        board.set(pos, Tile::Soft(digit));
+        Step::Place { pos, digit, reason }
+    }
+
+    /// Takes back the most recent choice, or returns `None` if there is none.
+    fn backtrack(&mut self, links: &mut Links, board: &mut Board) -> Option<Step> {
+        let (header, node) = self.stack.pop()?;
+        let mut j = links.left[node];
+        while j != node {
+            links.uncover(links.column[j]);
+            j = links.left[j];
+        }
+
+        let (pos, digit) = row_cell(links.row[node]);
+        board.set(pos, Tile::Empty);
+
+        let next = links.down[node];
+        if next != header {
+            self.retry = Some((header, next));
+            self.backtracking = false;
+        } else {
+            links.uncover(header);
+        }
+        Some(Step::Unplace { pos, digit })
+    }
+
+    fn step_with(&mut self, links: &mut Links, board: &mut Board) -> StepResult {
+        if self.unsolvable {
+            return StepResult::Unsolvable;
+        }
+
+        if let Some((header, node)) = self.retry.take() {
+            return StepResult::Solving(Self::select(links, &mut self.stack, board, header, node, Reason::Guess));
+        }
+
+        if !self.backtracking {
+            let Some(header) = links.smallest_column() else {
+                return StepResult::Finished;
+            };
+
+            if links.size[header] > 0 {
+                let reason = match links.size[header] {
+                    1 => Reason::Logic(forced_technique(header)),
+                    _ => Reason::Guess
+                };
+                links.cover(header);
+                let node = links.down[header];
+                return StepResult::Solving(Self::select(links, &mut self.stack, board, header, node, reason));
+            }
+            self.backtracking = true;
+        }
+
+        match self.backtrack(links, board) {
+            Some(step) => StepResult::Solving(step),
+            None => {
+                self.unsolvable = true;
+                StepResult::Unsolvable
+            }
+        }
+    }
+}
+
+impl Solver for DlxSolver {
+    fn step(&mut self, board: &mut Board) -> StepResult {
+        let mut links = match self.links.take() {
+            Some(links) => links,
+            None => self.init(board)
+        };
+        let result = self.step_with(&mut links, board);
+        self.links = Some(links);
+        result
+    }
+}
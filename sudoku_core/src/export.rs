@@ -0,0 +1,229 @@
+use std::fmt::Write;
+
+use crate::{
+    board::{get_pos, Board, Tile},
+    candidates::digits,
+    search::find_solution
+};
+
+/// How [`to_svg`] draws a board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// The width and height of one cell, in SVG user units.
+    pub cell_size: u32,
+    /// Draws the candidates of every empty cell in small print.
+    pub candidates: bool
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self { cell_size: 40, candidates: false }
+    }
+}
+
+/// Space around the grid, so the thick outer border isn't cut off.
+const MARGIN: u32 = 2;
+
+/// Draws `board` as a standalone SVG image.
+///
+/// Givens are drawn in bold, digits the solver placed in blue, and the box
+/// borders thicker than the cell borders.
+pub fn to_svg(board: &Board, options: SvgOptions) -> String {
+    let cell = options.cell_size;
+    let size = cell * 9 + MARGIN * 2;
+    let mut svg = String::new();
+
+    let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">"#);
+    let _ = writeln!(svg, r#"<rect width="{size}" height="{size}" fill="white"/>"#);
+
+    let _ = writeln!(svg, r#"<g font-family="sans-serif" text-anchor="middle" dominant-baseline="central">"#);
+    for idx in 0..81 {
+        let pos = get_pos(idx);
+        let (x, y) = (MARGIN + pos.0 as u32 * cell, MARGIN + pos.1 as u32 * cell);
+        let center = (x as f32 + cell as f32 / 2.0, y as f32 + cell as f32 / 2.0);
+        let font_size = cell as f32 * 0.6;
+        match board.get(pos) {
+            Tile::Hard(digit) => {
+                let _ = writeln!(svg, r#"<text x="{:.1}" y="{:.1}" font-size="{font_size:.1}" font-weight="bold">{digit}</text>"#, center.0, center.1);
+            },
+            Tile::Soft(digit) => {
+                let _ = writeln!(svg, r##"<text x="{:.1}" y="{:.1}" font-size="{font_size:.1}" fill="#2450a0">{digit}</text>"##, center.0, center.1);
+            },
+            Tile::Empty if options.candidates => {
+                let third = cell as f32 / 3.0;
+                for digit in digits(board.candidates(pos)) {
+                    let (col, row) = ((digit - 1) % 3, (digit - 1) / 3);
+                    let (cx, cy) = (x as f32 + (col as f32 + 0.5) * third, y as f32 + (row as f32 + 0.5) * third);
+                    let _ = writeln!(svg, r##"<text x="{cx:.1}" y="{cy:.1}" font-size="{:.1}" fill="#808080">{digit}</text>"##, third * 0.8);
+                }
+            },
+            Tile::Empty => ()
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+
+    let _ = writeln!(svg, r#"<g stroke="black" stroke-linecap="square">"#);
+    for i in 0..=9 {
+        let offset = MARGIN + i * cell;
+        let (start, end) = (MARGIN, MARGIN + 9 * cell);
+        let width = if i % 3 == 0 { 3 } else { 1 };
+        let _ = writeln!(svg, r#"<line x1="{offset}" y1="{start}" x2="{offset}" y2="{end}" stroke-width="{width}"/>"#);
+        let _ = writeln!(svg, r#"<line x1="{start}" y1="{offset}" x2="{end}" y2="{offset}" stroke-width="{width}"/>"#);
+    }
+    let _ = writeln!(svg, "</g>");
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Writes `board` for the `sudoku` LaTeX package, as a `sudoku` environment
+/// ready to paste into a document that has `\usepackage{sudoku}`.
+pub fn to_latex(board: &Board) -> String {
+    let mut latex = String::from("\\begin{sudoku}\n");
+    for row in board.tiles() {
+        for tile in row {
+            latex.push('|');
+            latex.push(tile.digit().map_or(' ', |digit| char::from(b'0' + digit)));
+        }
+        latex.push_str("|.\n");
+    }
+    latex.push_str("\\end{sudoku}\n");
+    latex
+}
+
+/// How [`to_pdf`] lays out puzzles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PdfOptions {
+    /// How many puzzles to fit on each page, in up to two columns.
+    pub per_page: usize,
+    /// Adds pages with the solution of every puzzle after the puzzles.
+    pub solutions: bool
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self { per_page: 1, solutions: false }
+    }
+}
+
+/// A4 in points.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const PAGE_MARGIN: f32 = 48.0;
+/// Space between two grids on a page.
+const GRID_GAP: f32 = 28.0;
+/// Room for the caption above each grid.
+const CAPTION_HEIGHT: f32 = 18.0;
+const SOLUTIONS_PER_PAGE: usize = 6;
+
+/// Lays out `puzzles` as a printable A4 PDF document.
+///
+/// Every puzzle is drawn with its givens and captioned with its number. With
+/// [`PdfOptions::solutions`], the solutions follow on pages of their own,
+/// six to a page, with the givens in bold; puzzles without a solution are left
+/// blank there.
+pub fn to_pdf(puzzles: &[Board], options: PdfOptions) -> Vec<u8> {
+    let mut pages = Vec::new();
+    for (chunk_idx, chunk) in puzzles.chunks(options.per_page.max(1)).enumerate() {
+        let first = chunk_idx * options.per_page.max(1);
+        let grids: Vec<(String, Board)> = chunk.iter().enumerate().map(|(i, puzzle)| (format!("Puzzle {}", first + i + 1), *puzzle)).collect();
+        pages.push(page_content(&grids, options.per_page.max(1)));
+    }
+
+    if options.solutions {
+        for (chunk_idx, chunk) in puzzles.chunks(SOLUTIONS_PER_PAGE).enumerate() {
+            let first = chunk_idx * SOLUTIONS_PER_PAGE;
+            let grids: Vec<(String, Board)> = chunk.iter().enumerate().map(|(i, puzzle)| {
+                (format!("Solution {}", first + i + 1), find_solution(puzzle).unwrap_or(*puzzle))
+            }).collect();
+            pages.push(page_content(&grids, SOLUTIONS_PER_PAGE));
+        }
+    }
+
+    write_pdf(&pages)
+}
+
+/// The drawing commands for one page of captioned grids.
+fn page_content(grids: &[(String, Board)], per_page: usize) -> String {
+    let columns = if per_page == 1 { 1 } else { 2 };
+    let rows = per_page.div_ceil(columns);
+    let width = (PAGE_WIDTH - 2.0 * PAGE_MARGIN - (columns - 1) as f32 * GRID_GAP) / columns as f32;
+    let height = (PAGE_HEIGHT - 2.0 * PAGE_MARGIN - (rows - 1) as f32 * GRID_GAP) / rows as f32 - CAPTION_HEIGHT;
+    let size = width.min(height);
+
+    // Center the block of grids on the page
+    let block_width = columns as f32 * size + (columns - 1) as f32 * GRID_GAP;
+    let block_height = rows as f32 * (size + CAPTION_HEIGHT) + (rows - 1) as f32 * GRID_GAP;
+    let left = (PAGE_WIDTH - block_width) / 2.0;
+    let top = PAGE_HEIGHT - (PAGE_HEIGHT - block_height) / 2.0;
+
+    let mut content = String::new();
+    for (i, (caption, board)) in grids.iter().enumerate() {
+        let x = left + (i % columns) as f32 * (size + GRID_GAP);
+        let y = top - (i / columns) as f32 * (size + CAPTION_HEIGHT + GRID_GAP);
+        let _ = writeln!(content, "BT /F1 11 Tf {x:.1} {:.1} Td ({caption}) Tj ET", y - 12.0);
+        draw_pdf_grid(&mut content, board, x, y - CAPTION_HEIGHT, size);
+    }
+    content
+}
+
+/// Draws a grid with its top left corner at `(x, top)` in PDF coordinates,
+/// which count upwards from the bottom of the page.
+fn draw_pdf_grid(content: &mut String, board: &Board, x: f32, top: f32, size: f32) {
+    let cell = size / 9.0;
+    let font_size = cell * 0.6;
+    for idx in 0..81 {
+        let pos = get_pos(idx);
+        let (font, digit) = match board.get(pos) {
+            Tile::Hard(digit) => ("F2", digit),
+            Tile::Soft(digit) => ("F1", digit),
+            Tile::Empty => continue
+        };
+        // Helvetica digits are 0.556 em wide and about 0.7 em tall
+        let text_x = x + (pos.0 as f32 + 0.5) * cell - font_size * 0.278;
+        let text_y = top - (pos.1 as f32 + 0.5) * cell - font_size * 0.35;
+        let _ = writeln!(content, "BT /{font} {font_size:.1} Tf {text_x:.1} {text_y:.1} Td ({digit}) Tj ET");
+    }
+
+    for i in 0..=9 {
+        let offset = i as f32 * cell;
+        let width = if i % 3 == 0 { 2.0 } else { 0.5 };
+        let _ = writeln!(content, "{width} w {:.1} {top:.1} m {:.1} {:.1} l S", x + offset, x + offset, top - size);
+        let _ = writeln!(content, "{width} w {x:.1} {:.1} m {:.1} {:.1} l S", top - offset, x + size, top - offset);
+    }
+}
+
+/// Wraps page contents into a PDF file using the built-in Helvetica fonts.
+fn write_pdf(pages: &[String]) -> Vec<u8> {
+    // Objects 1 to 4 are the catalog, the page tree and the two fonts, then
+    // every page is followed by its contents
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", 5 + 2 * i)).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold >>".to_string()
+    ];
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            6 + 2 * i
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{content}endstream", content.len()));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        let _ = writeln!(pdf, "{} 0 obj\n{object}\nendobj", i + 1);
+    }
+
+    let xref = pdf.len();
+    let _ = writeln!(pdf, "xref\n0 {}\n0000000000 65535 f ", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(pdf, "{offset:010} 00000 n ");
+    }
+    let _ = write!(pdf, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n", objects.len() + 1);
+    pdf.into_bytes()
+}
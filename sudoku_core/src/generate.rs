@@ -0,0 +1,154 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH}
+};
+
+use crate::{
+    board::{get_pos, Board, Tile},
+    difficulty::{rate, Difficulty},
+    minimize::minimize_random,
+    rng::Rng,
+    search::{count_solutions, random_solution}
+};
+
+/// How [`generate_with`] builds a puzzle around a random solution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GenerationStrategy {
+    /// Starts from the full solution and removes givens for as long as the
+    /// solution stays unique. Always ends with an irreducible puzzle, usually
+    /// with low to mid twenties of givens, at the cost of one uniqueness
+    /// check per cell.
+    #[default]
+    Dig,
+    /// Starts from an empty board and adds givens from the solution until it
+    /// is unique. Needs about a third as many checks, but checks on sparse
+    /// boards are the slow kind, and many givens end up redundant, so puzzles
+    /// have around twice as many of them and are nearly always easy.
+    Add
+}
+
+impl GenerationStrategy {
+    /// The name used for this strategy on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            GenerationStrategy::Dig => "dig",
+            GenerationStrategy::Add => "add"
+        }
+    }
+
+    /// Looks up a strategy by [`GenerationStrategy::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        [GenerationStrategy::Dig, GenerationStrategy::Add].into_iter().find(|strategy| strategy.name() == name)
+    }
+}
+
+/// A generated puzzle, along with what it took to make it.
+#[derive(Clone, Copy, Debug)]
+pub struct Generated {
+    pub puzzle: Board,
+    pub strategy: GenerationStrategy,
+    /// The number of givens in the puzzle.
+    pub clues: usize,
+    /// How many times the generator checked whether the solution was unique,
+    /// including on puzzles it threw away.
+    pub checks: usize,
+    pub time: Duration
+}
+
+/// Generates a random puzzle with a unique solution.
+///
+/// This uses [`GenerationStrategy::Dig`], so no given can be removed from the
+/// result. The same generator state always gives the same puzzle.
+pub fn generate(rng: &mut Rng) -> Board {
+    generate_with(GenerationStrategy::Dig, rng).puzzle
+}
+
+/// Generates a random puzzle with a unique solution using `strategy`.
+pub fn generate_with(strategy: GenerationStrategy, rng: &mut Rng) -> Generated {
+    let start = Instant::now();
+    let solution = random_solution(&Board::new(), rng).expect("the empty board has solutions");
+    let (puzzle, checks) = match strategy {
+        GenerationStrategy::Dig => dig(&solution, rng),
+        GenerationStrategy::Add => add(&solution, rng)
+    };
+    Generated { puzzle, strategy, clues: puzzle.clues(), checks, time: start.elapsed() }
+}
+
+/// Generates puzzles with [`GenerationStrategy::Dig`] until one is rated `difficulty`.
+///
+/// Harder puzzles can take many attempts, so `on_attempt` is called with the
+/// number of the attempt about to start, and the search gives up and returns
+/// `None` as soon as `cancel` is set. This is meant to run on a worker thread.
+pub fn generate_rated(
+    difficulty: Difficulty,
+    rng: &mut Rng,
+    cancel: &AtomicBool,
+    mut on_attempt: impl FnMut(usize)
+) -> Option<Generated> {
+    let start = Instant::now();
+    let mut checks = 0;
+    let mut attempt = 0;
+    while !cancel.load(Ordering::Relaxed) {
+        attempt += 1;
+        on_attempt(attempt);
+
+        let generated = generate_with(GenerationStrategy::Dig, rng);
+        checks += generated.checks;
+        if rate(&generated.puzzle) == Some(difficulty) {
+            return Some(Generated { checks, time: start.elapsed(), ..generated });
+        }
+    }
+    None
+}
+
+fn dig(solution: &Board, rng: &mut Rng) -> (Board, usize) {
+    let puzzle = minimize_random(&as_givens(solution), rng).expect("a full board has a unique solution");
+    // Minimizing checks the board once up front and then once per given
+    (puzzle, 1 + 81)
+}
+
+fn add(solution: &Board, rng: &mut Rng) -> (Board, usize) {
+    let mut order: Vec<usize> = (0..81).collect();
+    rng.shuffle(&mut order);
+
+    let mut puzzle = Board::new();
+    let mut checks = 0;
+    for (added, pos) in order.into_iter().map(get_pos).enumerate() {
+        if let Some(digit) = solution.get(pos).digit() {
+            puzzle.set(pos, Tile::Hard(digit));
+        }
+        // No puzzle with fewer than 17 givens has a unique solution
+        if added + 1 >= 17 {
+            checks += 1;
+            if count_solutions(&puzzle, 2) == 1 {
+                break;
+            }
+        }
+    }
+    (puzzle, checks)
+}
+
+/// The puzzle of the day for the UTC date at `time`.
+///
+/// Everyone gets the same puzzle on the same day, as long as the generator
+/// itself doesn't change.
+pub fn daily_puzzle(time: SystemTime) -> Board {
+    generate(&mut Rng::new(day_number(time)))
+}
+
+/// Days since the Unix epoch, in UTC.
+pub fn day_number(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs() / 86_400)
+}
+
+/// Turns every filled tile into a given.
+fn as_givens(board: &Board) -> Board {
+    let mut givens = Board::new();
+    for idx in 0..81 {
+        let pos = get_pos(idx);
+        if let Some(digit) = board.get(pos).digit() {
+            givens.set(pos, Tile::Hard(digit));
+        }
+    }
+    givens
+}
@@ -0,0 +1,53 @@
+//! Board representation, validation and solving for the sudoku solver.
+//!
+//! This crate has no dependency on SDL2, so the solver can be used from other
+//! programs and tests without a display.
+
+mod assist;
+mod backtracker;
+mod batch;
+mod board;
+mod candidates;
+mod coloring;
+mod csv;
+mod difficulty;
+mod dlx;
+mod export;
+mod generate;
+mod minimize;
+mod notes;
+mod parallel;
+mod progress;
+pub mod render;
+mod rng;
+#[cfg(feature = "sat")]
+pub mod sat;
+mod sdk;
+mod search;
+mod share;
+mod solver;
+mod techniques;
+
+pub use assist::{fill_unit, hint};
+pub use backtracker::Backtracker;
+pub use batch::{solve_all, SolveResult, SolveStatus};
+pub use board::{box_index, cell_name, get_pos, Board, ParseBoardError, Tile, Unit};
+pub use candidates::{digits, Candidates, ALL_DIGITS};
+pub use coloring::{Coloring, COLOR_COUNT};
+pub use csv::{parse_csv, CsvPuzzle};
+pub use difficulty::{rate, Difficulty};
+pub use dlx::DlxSolver;
+pub use export::{to_latex, to_pdf, to_svg, PdfOptions, SvgOptions};
+pub use generate::{daily_puzzle, day_number, generate, generate_rated, generate_with, GenerationStrategy, Generated};
+pub use minimize::{minimize, minimize_random};
+pub use notes::Notes;
+pub use parallel::{default_threads, find_solution_parallel, generate_many, solve_many};
+pub use progress::{solve_with_budget, Budget, Outcome, Progress};
+pub use rng::Rng;
+pub use sdk::{parse_sdm, ParseCollectionError, SdkMetadata, SdkPuzzle};
+pub use search::{count_solutions, find_solution, random_solution};
+pub use share::{from_share_code, to_share_code, ParseCodeError, SHARE_PREFIX};
+pub use solver::{CellOrder, Reason, SolveLog, Solver, SolverOptions, Step, StepResult, Strategy};
+pub use techniques::{
+    hidden_single, hidden_subset, locked_candidates, naked_single, naked_subset, Deduction, Technique, Techniques
+};
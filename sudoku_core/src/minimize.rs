@@ -0,0 +1,46 @@
+use crate::{
+    board::{get_pos, Board, Tile},
+    rng::Rng,
+    search::count_solutions
+};
+
+/// Removes givens from `board` until every one left is needed to keep the
+/// solution unique.
+///
+/// Givens are tried in reading order. A single pass is enough, since taking
+/// more givens away never makes an earlier one unnecessary. `Soft` tiles are
+/// dropped. Returns `None` if the board doesn't have exactly one solution to
+/// begin with; the number of givens left is [`Board::clues`].
+pub fn minimize(board: &Board) -> Option<Board> {
+    let order: Vec<usize> = (0..81).collect();
+    minimize_in_order(board, &order)
+}
+
+/// Like [`minimize`], but tries the givens in a random order, which can
+/// leave a different set of them.
+pub fn minimize_random(board: &Board, rng: &mut Rng) -> Option<Board> {
+    let mut order: Vec<usize> = (0..81).collect();
+    rng.shuffle(&mut order);
+    minimize_in_order(board, &order)
+}
+
+fn minimize_in_order(board: &Board, order: &[usize]) -> Option<Board> {
+    let mut board = *board;
+    board.clear_soft();
+    if count_solutions(&board, 2) != 1 {
+        return None;
+    }
+
+    for pos in order.iter().map(|&idx| get_pos(idx)) {
+        let tile = board.get(pos);
+        if tile == Tile::Empty {
+            continue;
+        }
+
+        board.set(pos, Tile::Empty);
+        if count_solutions(&board, 2) != 1 {
+            board.set(pos, tile);
+        }
+    }
+    Some(board)
+}
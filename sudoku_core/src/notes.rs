@@ -0,0 +1,66 @@
+use crate::{
+    board::{Board, Unit},
+    candidates::Candidates
+};
+
+/// The candidates the player has pencilled into each cell, kept apart from the
+/// board so they never affect solving. Masks have bit `n` set for digit `n`,
+/// as in [`crate::Candidates`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Notes {
+    cells: [[u16; 9]; 9]
+}
+
+impl Notes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notes every candidate left in every empty cell, as the solver sees them.
+    pub fn from_candidates(candidates: &Candidates) -> Self {
+        let mut notes = Self::new();
+        for y in 0..9 {
+            for x in 0..9 {
+                notes.set((x, y), candidates.get((x, y)));
+            }
+        }
+        notes
+    }
+
+    /// The digits noted in the cell at `pos`.
+    pub fn get(&self, pos: (usize, usize)) -> u16 {
+        self.cells[pos.1][pos.0]
+    }
+
+    pub fn set(&mut self, pos: (usize, usize), mask: u16) {
+        self.cells[pos.1][pos.0] = mask;
+    }
+
+    /// Notes `digit` in the cell at `pos`, or takes it out if it was there.
+    pub fn toggle(&mut self, pos: (usize, usize), digit: u8) {
+        self.cells[pos.1][pos.0] ^= 1 << digit;
+    }
+
+    /// Takes every digit placed on the way from `before` to `after` out of
+    /// the notes of the cells that see it, whoever placed it.
+    pub fn eliminate_placed(&mut self, before: &Board, after: &Board) {
+        for y in 0..9 {
+            for x in 0..9 {
+                let Some(digit) = after.get((x, y)).digit() else {
+                    continue;
+                };
+                if before.get((x, y)).digit() == Some(digit) {
+                    continue;
+                }
+                for unit in Unit::containing((x, y)) {
+                    for (px, py) in unit.cells() {
+                        if (px, py) != (x, y) {
+                            self.cells[py][px] &= !(1 << digit);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
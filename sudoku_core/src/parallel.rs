@@ -0,0 +1,105 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex
+    },
+    thread
+};
+
+use crate::{
+    board::{Board, Tile},
+    candidates::digits,
+    generate::{generate_with, GenerationStrategy, Generated},
+    rng::Rng,
+    search::{find_solution, find_solution_until, most_constrained}
+};
+
+/// The number of worker threads to use by default: one per available core.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Solves every board on a pool of `threads` worker threads.
+///
+/// Workers pull the next unsolved board from a shared counter, so a few slow
+/// boards don't hold up the rest. Results are in the same order as `boards`,
+/// with `None` for boards that have no solution.
+pub fn solve_many(boards: &[Board], threads: usize) -> Vec<Option<Board>> {
+    let next = AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<Board>>> = boards.iter().map(|_| Mutex::new(None)).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, boards.len().max(1)) {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(board) = boards.get(idx) else { break };
+                *results[idx].lock().unwrap() = find_solution(board);
+            });
+        }
+    });
+
+    results.into_iter().map(|result| result.into_inner().unwrap()).collect()
+}
+
+/// Generates `count` puzzles with `strategy` on a pool of `threads` worker threads.
+///
+/// Every puzzle gets its own seed drawn from `rng` up front, so the result
+/// only depends on `rng` and not on how the work is split between threads.
+pub fn generate_many(count: usize, threads: usize, strategy: GenerationStrategy, rng: &mut Rng) -> Vec<Generated> {
+    let seeds: Vec<u64> = (0..count).map(|_| rng.next_u64()).collect();
+    let next = AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<Generated>>> = seeds.iter().map(|_| Mutex::new(None)).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, count.max(1)) {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&seed) = seeds.get(idx) else { break };
+                *results[idx].lock().unwrap() = Some(generate_with(strategy, &mut Rng::new(seed)));
+            });
+        }
+    });
+
+    results.into_iter().map(|result| result.into_inner().unwrap().expect("every seed is taken by a worker")).collect()
+}
+
+/// Solves a single board by splitting the first guess across threads.
+///
+/// Each candidate of the most constrained cell is searched on its own thread,
+/// up to `threads` at a time. As soon as one branch finds a solution the
+/// others are cancelled. Returns `None` if the board has no solution.
+pub fn find_solution_parallel(board: &Board, threads: usize) -> Option<Board> {
+    if !board.is_valid() {
+        return None;
+    }
+    let Some((pos, mask)) = most_constrained(board) else {
+        return Some(*board);
+    };
+
+    let branches: Vec<Board> = digits(mask)
+        .map(|digit| {
+            let mut branch = *board;
+            branch.set(pos, Tile::Soft(digit));
+            branch
+        })
+        .collect();
+
+    let next = AtomicUsize::new(0);
+    let found = AtomicBool::new(false);
+    let solution = Mutex::new(None);
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, branches.len().max(1)) {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(branch) = branches.get(idx) else { break };
+                if let Some(result) = find_solution_until(branch, &found) {
+                    found.store(true, Ordering::Relaxed);
+                    solution.lock().unwrap().get_or_insert(result);
+                }
+            });
+        }
+    });
+
+    solution.into_inner().unwrap()
+}
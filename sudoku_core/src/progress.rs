@@ -0,0 +1,124 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    board::Board,
+    solver::{Solver, Step, StepResult}
+};
+
+/// How far a solve has come, updated after every step.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// Solver steps taken, of any kind.
+    pub steps: u64,
+    /// Digits placed, including ones later taken back.
+    pub nodes: u64,
+    /// Placements taken back.
+    pub backtracks: u64,
+    /// Filled tiles on the board, givens included.
+    pub filled: usize
+}
+
+impl Progress {
+    /// Starts tracking a solve of `board`.
+    pub fn new(board: &Board) -> Self {
+        Self { filled: filled(board), ..Self::default() }
+    }
+
+    /// Accounts for one step the solver just made.
+    pub fn record(&mut self, step: &Step) {
+        self.steps += 1;
+        match step {
+            Step::Place { .. } => {
+                self.nodes += 1;
+                self.filled += 1;
+            },
+            Step::Unplace { .. } => {
+                self.backtracks += 1;
+                self.filled -= 1;
+            },
+            Step::Eliminate { .. } | Step::Restore { .. } => ()
+        }
+    }
+
+    /// Takes back a step previously passed to [`Progress::record`], as when
+    /// rewinding a solve.
+    pub fn unrecord(&mut self, step: &Step) {
+        self.steps -= 1;
+        match step {
+            Step::Place { .. } => {
+                self.nodes -= 1;
+                self.filled -= 1;
+            },
+            Step::Unplace { .. } => {
+                self.backtracks -= 1;
+                self.filled += 1;
+            },
+            Step::Eliminate { .. } | Step::Restore { .. } => ()
+        }
+    }
+
+    /// The share of the board that is filled, from 0 to 1.
+    pub fn fraction_filled(&self) -> f32 {
+        self.filled as f32 / 81.0
+    }
+}
+
+fn filled(board: &Board) -> usize {
+    board.tiles().iter().flatten().filter(|tile| tile.digit().is_some()).count()
+}
+
+/// Limits on how much work a solve may do before giving up.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Budget {
+    pub max_steps: Option<u64>,
+    pub max_time: Option<Duration>
+}
+
+/// How a budgeted solve ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Solved,
+    Unsolvable,
+    /// The budget ran out before the solver finished.
+    OutOfBudget
+}
+
+/// How many steps pass between progress reports and clock checks.
+const REPORT_INTERVAL: u64 = 1024;
+
+/// Runs `solver` on `board` until it finishes or the budget runs out.
+///
+/// `on_progress` is called every thousand or so steps and once at the end,
+/// so it is cheap enough to drive a progress bar. Returns the outcome and the
+/// final progress.
+pub fn solve_with_budget(
+    solver: &mut dyn Solver,
+    board: &mut Board,
+    budget: Budget,
+    mut on_progress: impl FnMut(&Progress)
+) -> (Outcome, Progress) {
+    let start = Instant::now();
+    let mut progress = Progress::new(board);
+
+    let outcome = loop {
+        if budget.max_steps.is_some_and(|max| progress.steps >= max) {
+            break Outcome::OutOfBudget;
+        }
+
+        match solver.step(board) {
+            StepResult::Solving(step) => progress.record(&step),
+            StepResult::Finished => break Outcome::Solved,
+            StepResult::Unsolvable => break Outcome::Unsolvable
+        }
+
+        if progress.steps.is_multiple_of(REPORT_INTERVAL) {
+            on_progress(&progress);
+            if budget.max_time.is_some_and(|max| start.elapsed() >= max) {
+                break Outcome::OutOfBudget;
+            }
+        }
+    };
+
+    on_progress(&progress);
+    (outcome, progress)
+}
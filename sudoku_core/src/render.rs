@@ -0,0 +1,624 @@
+use crate::{
+    board::{Board, Tile, Unit},
+    candidates::digits,
+    coloring::{Coloring, COLOR_COUNT},
+    notes::Notes,
+    progress::Progress,
+    solver::Step
+};
+
+/// A color as red, green and blue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// The color `amount` of the way from this one to `other`.
+    pub fn blend(self, other: Rgb, amount: f32) -> Rgb {
+        let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * amount.clamp(0.0, 1.0)).round() as u8;
+        Rgb(mix(self.0, other.0), mix(self.1, other.1), mix(self.2, other.2))
+    }
+}
+
+/// A digit placed by the solver part of the way through fading in or out of a cell.
+#[derive(Clone, Copy, Debug)]
+pub struct Fade {
+    pub pos: (i8, i8),
+    pub digit: u8,
+    /// How visible the digit is, from 0 for not at all to 1 for fully.
+    pub opacity: f32
+}
+
+/// Something a board can be drawn on, such as a window or a browser canvas.
+///
+/// Positions are in the surface's own units, with the board filling a square
+/// of nine [`Surface::tile_size`] from the top left corner, the panel, if
+/// drawn, [`PANEL_WIDTH`] more to the right of it, and the status bar, if
+/// drawn, [`status_height`] below them.
+pub trait Surface {
+    /// The size of one cell.
+    fn tile_size(&self) -> u32;
+
+    fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32, color: Rgb);
+
+    /// Draws one line of `text` centered on `center`, sized to fit in a cell.
+    fn text(&mut self, text: &str, center: (i32, i32), color: Rgb);
+
+    /// Like [`Surface::text`], but a third of the size, for the notes in a cell.
+    fn small_text(&mut self, text: &str, center: (i32, i32), color: Rgb);
+}
+
+/// The colors a board is drawn with.
+#[derive(Clone, Copy, Debug)]
+pub struct Palette {
+    pub grid: Rgb,
+    /// Givens and digits written by hand.
+    pub digit: Rgb,
+    /// Digits placed by the solver.
+    pub soft_digit: Rgb,
+    /// The fill of cells with a given.
+    pub given: Rgb,
+    /// The fill of a given under the cursor.
+    pub given_cursor: Rgb,
+    pub cursor: Rgb,
+    /// The row, column or box selected for filling.
+    pub region: Rgb,
+    /// Cells whose candidates the solver just changed.
+    pub changed: Rgb,
+    /// Other cells with the same digit as the cursor's.
+    pub same_digit: Rgb,
+    /// The row, column and box of the cursor.
+    pub peers: Rgb,
+    /// The outline of entries that don't match the solution.
+    pub wrong: Rgb,
+    pub progress: Rgb,
+    /// Whether givens are underlined, so they stand out without their fill.
+    pub underline_givens: bool,
+    /// How wide the borders around the boxes and the board are, where the
+    /// lines between cells are one wide.
+    pub box_border: u32
+}
+
+/// The colors cells can be marked with, from the Okabe-Ito colors so they
+/// stay apart for colorblind players. Blended into the background when drawn.
+const MARK_COLORS: [Rgb; COLOR_COUNT as usize] = [Rgb(230, 159, 0), Rgb(86, 180, 233), Rgb(0, 158, 115), Rgb(204, 121, 167)];
+
+/// How many cells wide the panel of buttons beside the board is.
+pub const PANEL_WIDTH: u32 = 3;
+
+/// A button on the panel beside the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Button {
+    Digit(u8),
+    Erase,
+    Notes,
+    Undo,
+    Hint,
+    Solve
+}
+
+/// How tall the status bar under the board is.
+pub fn status_height(tile_size: u32) -> u32 {
+    tile_size / 2
+}
+
+/// The buttons under the digits, a row each, with their labels.
+const PANEL_ROWS: [(Button, &str); 5] = [
+    (Button::Erase, "Erase"),
+    (Button::Notes, "Notes"),
+    (Button::Undo, "Undo"),
+    (Button::Hint, "Hint"),
+    (Button::Solve, "Solve")
+];
+
+/// What the panel shows as switched on.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Panel {
+    pub note_mode: bool,
+    /// The digit picked for placing, when it is picked first.
+    pub digit: Option<u8>,
+    /// Whether the solver is running, which the solve button pauses.
+    pub solving: bool
+}
+
+/// Everything drawn besides the digits and the grid.
+pub struct Scene<'a> {
+    pub palette: &'a Palette,
+    pub background: Rgb,
+    pub cursor: Option<(i8, i8)>,
+    /// The row, column or box selected for filling.
+    pub region: Option<Unit>,
+    /// Shown as a bar along the bottom while a solve is underway.
+    pub progress: Option<&'a Progress>,
+    /// A message across the middle of the board, with the color of its text
+    /// and border.
+    pub banner: Option<(&'a str, Rgb)>,
+    /// The solver's latest step, whose changed cells are tinted.
+    pub last_step: Option<&'a Step>,
+    /// The cursors of other players, outlined in their colors.
+    pub players: &'a [((i8, i8), Rgb)],
+    /// The player's pencil marks, drawn small in empty cells.
+    pub notes: Option<&'a Notes>,
+    /// The colors the player marked cells with, filling them.
+    pub coloring: Option<&'a Coloring>,
+    /// The cell the digit picker is open on.
+    pub picker: Option<(i8, i8)>,
+    /// Entries that don't match the solution.
+    pub wrong: &'a [(i8, i8)],
+    /// Cells picked along with the cursor, filled like it.
+    pub selection: &'a [(i8, i8)],
+    /// The digit picked for placing, whose cells are tinted rather than the
+    /// cursor's digit's.
+    pub digit: Option<u8>,
+    /// Buttons drawn to the right of the board, on surfaces with room for them.
+    pub panel: Option<Panel>,
+    /// The items of a menu drawn in place of the board, with the chosen one.
+    pub menu: Option<(&'a [&'a str], usize)>,
+    /// Short pieces of text spread along a bar under the board, or nothing
+    /// for no bar.
+    pub status: &'a [String],
+    /// Digits fading in where they were just placed, or out of the cells
+    /// they were just taken from.
+    pub fades: &'a [Fade],
+    /// Lines of text in a box over the middle of the board, as when the
+    /// puzzle is solved, or nothing for no box.
+    pub overlay: &'a [String],
+    /// A short note across the top of the board, over the overlay too, with
+    /// the color of its text and border.
+    pub toast: Option<(&'a str, Rgb)>,
+    /// Lines of small text in columns covering the board and the panel, as
+    /// for listing the keys, or nothing for no help.
+    pub help: &'a [String]
+}
+
+/// Draws `board` and everything in `scene` on `surface`.
+pub fn draw(board: &Board, scene: &Scene, surface: &mut dyn Surface) {
+    for y in 0..9 {
+        for x in 0..9 {
+            draw_cell(board, scene, surface, (x, y));
+        }
+    }
+    draw_over(board, scene, surface);
+}
+
+/// One call drawn on a surface, noted to tell whether a cell would be drawn
+/// the same as before.
+#[derive(Clone, Debug, PartialEq)]
+enum Op {
+    Fill(i32, i32, u32, u32, Rgb),
+    Text(String, (i32, i32), Rgb),
+    SmallText(String, (i32, i32), Rgb)
+}
+
+/// A surface that only notes what is drawn on it, to be drawn later on another.
+struct Recording {
+    tile_size: u32,
+    ops: Vec<Op>
+}
+
+impl Surface for Recording {
+    fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32, color: Rgb) {
+        self.ops.push(Op::Fill(x, y, width, height, color));
+    }
+
+    fn text(&mut self, text: &str, center: (i32, i32), color: Rgb) {
+        self.ops.push(Op::Text(text.to_string(), center, color));
+    }
+
+    fn small_text(&mut self, text: &str, center: (i32, i32), color: Rgb) {
+        self.ops.push(Op::SmallText(text.to_string(), center, color));
+    }
+}
+
+/// How each cell was last drawn by [`draw_changed`], which then skips the cells
+/// that would be drawn the same.
+#[derive(Debug, Default)]
+pub struct Drawn {
+    /// Each cell's calls in reading order, or nothing when every cell needs drawing.
+    cells: Vec<Vec<Op>>,
+    /// Whether the progress bar was drawn over the bottom row.
+    progress: bool
+}
+
+impl Drawn {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Has every cell drawn on the next frame, as when the surface was cleared.
+    pub fn forget(&mut self) {
+        self.cells.clear();
+    }
+}
+
+/// Draws like [`draw`], except that cells are left alone if they would be
+/// drawn the same as when `drawn` last saw them, for surfaces that keep what
+/// is drawn on them from one frame to the next. Anything drawn across the
+/// cells, such as a banner or the menu, has them all drawn again once it's gone.
+pub fn draw_changed(board: &Board, scene: &Scene, surface: &mut dyn Surface, drawn: &mut Drawn) {
+    let tile_size = surface.tile_size();
+    let mut cells = Vec::with_capacity(81);
+    for y in 0..9 {
+        for x in 0..9 {
+            let mut recording = Recording { tile_size, ops: Vec::new() };
+            draw_cell(board, scene, &mut recording, (x, y));
+            // The progress bar is drawn over the bottom row afresh every frame
+            let under_progress = y == 8 && (scene.progress.is_some() || drawn.progress);
+            if under_progress || drawn.cells.get(y * 9 + x) != Some(&recording.ops) {
+                for op in &recording.ops {
+                    match op {
+                        Op::Fill(x, y, width, height, color) => surface.fill_rect(*x, *y, *width, *height, *color),
+                        Op::Text(text, center, color) => surface.text(text, *center, *color),
+                        Op::SmallText(text, center, color) => surface.small_text(text, *center, *color)
+                    }
+                }
+            }
+            cells.push(recording.ops);
+        }
+    }
+    let covered = scene.banner.is_some() || !scene.overlay.is_empty() || scene.toast.is_some() || scene.picker.is_some() || scene.menu.is_some() || !scene.help.is_empty();
+    drawn.cells = if covered { Vec::new() } else { cells };
+    drawn.progress = scene.progress.is_some();
+    draw_over(board, scene, surface);
+}
+
+/// Everything in the cell at `pos`: its fill, digit or notes, outlines and
+/// the grid lines that run through it.
+fn draw_cell(board: &Board, scene: &Scene, surface: &mut dyn Surface, pos: (usize, usize)) {
+    let tile_size = surface.tile_size();
+    let cell = (pos.0 as i8, pos.1 as i8);
+    fill_cell(surface, cell, scene.background);
+
+    if let Some(cursor) = scene.cursor
+        && Unit::containing((cursor.0 as usize, cursor.1 as usize)).iter().any(|unit| unit.cells().contains(&pos))
+    {
+        fill_cell(surface, cell, scene.palette.peers);
+    }
+
+    if let Some(color) = scene.coloring.map(|coloring| coloring.get(pos)).filter(|&color| color > 0) {
+        fill_cell(surface, cell, scene.background.blend(MARK_COLORS[color as usize - 1], 0.5));
+    }
+
+    if let Some(Step::Eliminate { eliminations, .. } | Step::Restore { eliminations }) = scene.last_step
+        && eliminations.iter().any(|&(eliminated, _)| eliminated == pos)
+    {
+        fill_cell(surface, cell, scene.palette.changed);
+    }
+
+    if scene.region.is_some_and(|region| region.cells().contains(&pos)) {
+        fill_cell(surface, cell, scene.palette.region);
+    }
+
+    if scene.selection.contains(&cell) || scene.cursor == Some(cell) {
+        fill_cell(surface, cell, scene.palette.cursor);
+    }
+    draw_number(board, scene, surface, pos);
+    if let Some(notes) = scene.notes {
+        draw_notes(board, notes, scene.palette, surface, pos);
+    }
+
+    let corner = |inset: u32| ((pos.0 as u32 * tile_size + inset) as i32, (pos.1 as u32 * tile_size + inset) as i32);
+    if scene.wrong.contains(&cell) {
+        for inset in 1..3 {
+            outline(surface, corner(inset), (tile_size - 2 * inset, tile_size - 2 * inset), scene.palette.wrong);
+        }
+    }
+
+    for &(_, color) in scene.players.iter().filter(|&&(player, _)| player == cell) {
+        for inset in 1..3 {
+            outline(surface, corner(inset), (tile_size - 2 * inset, tile_size - 2 * inset), color);
+        }
+    }
+
+    draw_grid(surface, scene.palette, pos);
+}
+
+/// What is drawn over the cells, and the panel and status bar beside them.
+fn draw_over(board: &Board, scene: &Scene, surface: &mut dyn Surface) {
+    let tile_size = surface.tile_size();
+    let size = tile_size * 9;
+
+    if let Some(progress) = scene.progress {
+        let width = (size as f32 * progress.fraction_filled()) as u32;
+        surface.fill_rect(0, (size - 3) as _, width, 3, scene.palette.progress);
+    }
+
+    if let Some((text, color)) = scene.banner {
+        surface.fill_rect(0, (4 * tile_size) as _, size, tile_size, scene.background);
+        outline(surface, (0, (4 * tile_size) as _), (size, tile_size), color);
+        surface.text(text, ((size / 2) as _, (4 * tile_size + tile_size / 2) as _), color);
+    }
+
+    if !scene.overlay.is_empty() {
+        draw_overlay(scene.overlay, scene, surface);
+    }
+
+    if let Some((text, color)) = scene.toast {
+        surface.fill_rect(0, 0, size, tile_size, scene.background);
+        outline(surface, (0, 0), (size, tile_size), color);
+        surface.text(text, ((size / 2) as _, (tile_size / 2) as _), color);
+    }
+
+    if let Some(cell) = scene.picker {
+        draw_picker(cell, scene, surface);
+    }
+
+    if let Some((items, selected)) = scene.menu {
+        draw_menu(items, selected, scene, surface);
+    }
+
+    if let Some(panel) = scene.panel {
+        draw_panel(board, &panel, scene, surface);
+    }
+
+    if !scene.help.is_empty() {
+        draw_help(scene, surface);
+    }
+
+    if !scene.status.is_empty() {
+        draw_status(scene, surface);
+    }
+}
+
+/// The status pieces in small text, each centered in an equal share of the
+/// bar, which spans the panel too when there is one.
+fn draw_status(scene: &Scene, surface: &mut dyn Surface) {
+    let tile_size = surface.tile_size();
+    let width = tile_size * (9 + if scene.panel.is_some() { PANEL_WIDTH } else { 0 });
+    let (top, height) = ((tile_size * 9) as i32, status_height(tile_size));
+    surface.fill_rect(0, top, width, height, scene.background);
+    surface.fill_rect(0, top, width, 1, scene.palette.grid);
+    let share = width / scene.status.len() as u32;
+    for (i, text) in scene.status.iter().enumerate() {
+        surface.small_text(text, ((i as u32 * share + share / 2) as i32, top + height as i32 / 2), scene.palette.digit);
+    }
+}
+
+/// The top left corner of the first of `count` menu items, which are stacked
+/// in the middle of the board, five cells wide and one high, or from the top
+/// if there are more than fit.
+fn menu_origin(count: usize, tile_size: u32) -> (i32, i32) {
+    ((tile_size * 2) as i32, (9u32.saturating_sub(count as u32) / 2 * tile_size) as i32)
+}
+
+/// The menu item under `point`, if any, out of `count`.
+pub fn menu_item(point: (i32, i32), count: usize, tile_size: u32) -> Option<usize> {
+    let origin = menu_origin(count, tile_size);
+    let (x, y) = (point.0 - origin.0, point.1 - origin.1);
+    ((0..(tile_size * 5) as i32).contains(&x) && (0..(tile_size * count as u32) as i32).contains(&y)).then(|| (y / tile_size as i32) as usize)
+}
+
+/// The menu's items over a blank board, so the puzzle can't be studied with the timer paused.
+fn draw_menu(items: &[&str], selected: usize, scene: &Scene, surface: &mut dyn Surface) {
+    let tile_size = surface.tile_size();
+    surface.fill_rect(0, 0, tile_size * 9, tile_size * 9, scene.background);
+    let origin = menu_origin(items.len(), tile_size);
+    for (i, item) in items.iter().enumerate() {
+        let corner = (origin.0, origin.1 + (i as u32 * tile_size) as i32);
+        let fill = if i == selected { scene.palette.region } else { scene.background };
+        surface.fill_rect(corner.0, corner.1, tile_size * 5, tile_size, fill);
+        outline(surface, corner, (tile_size * 5, tile_size), scene.palette.grid);
+        surface.text(item, (corner.0 + (tile_size * 5 / 2) as i32, corner.1 + tile_size as i32 / 2), scene.palette.digit);
+    }
+}
+
+/// The button on the panel under `point`, if any. The digits are laid out like
+/// the picker's in the top three rows, with the other buttons in a row each below.
+pub fn panel_button(point: (i32, i32), tile_size: u32) -> Option<Button> {
+    let (x, y) = (point.0 - (tile_size * 9) as i32, point.1);
+    if !(0..(tile_size * PANEL_WIDTH) as i32).contains(&x) || y < 0 {
+        return None;
+    }
+    let (column, row) = ((x / tile_size as i32) as usize, (y / tile_size as i32) as usize);
+    match row {
+        0..3 => Some(Button::Digit((row * 3 + column + 1) as u8)),
+        _ => PANEL_ROWS.get(row - 3).map(|&(button, _)| button)
+    }
+}
+
+/// The buttons, with how many of each digit are on the board in the corner of
+/// its button, and the digits placed nine times greyed out.
+fn draw_panel(board: &Board, panel: &Panel, scene: &Scene, surface: &mut dyn Surface) {
+    let tile_size = surface.tile_size();
+    let left = (tile_size * 9) as i32;
+    surface.fill_rect(left, 0, tile_size * PANEL_WIDTH, tile_size * 9, scene.background);
+    let (digit, background) = (scene.palette.digit, scene.background);
+    let grey = digit.blend(background, 0.5);
+    let mut placed = [0; 10];
+    for tile in board.tiles().iter().flatten() {
+        if let Some(num) = tile.digit() {
+            placed[num as usize] += 1;
+        }
+    }
+    for num in 1..=9u8 {
+        let corner = (left + ((num as u32 - 1) % 3 * tile_size) as i32, ((num as u32 - 1) / 3 * tile_size) as i32);
+        let color = if placed[num as usize] >= 9 { grey } else { digit };
+        draw_button(corner, tile_size, &num.to_string(), panel.digit == Some(num), color, scene, surface);
+        let center = (corner.0 + (tile_size * 5 / 6) as i32, corner.1 + (tile_size * 5 / 6) as i32);
+        surface.small_text(&placed[num as usize].to_string(), center, grey);
+    }
+    for (row, (button, label)) in PANEL_ROWS.into_iter().enumerate() {
+        let (label, on) = match button {
+            Button::Notes => (label, panel.note_mode),
+            Button::Solve if panel.solving => ("Pause", true),
+            _ => (label, false)
+        };
+        draw_button((left, ((row as u32 + 3) * tile_size) as i32), tile_size * PANEL_WIDTH, label, on, digit, scene, surface);
+    }
+}
+
+/// A button a cell high and `width` wide, filled while it is `on`, with its label in `color`.
+fn draw_button(corner: (i32, i32), width: u32, label: &str, on: bool, color: Rgb, scene: &Scene, surface: &mut dyn Surface) {
+    let tile_size = surface.tile_size();
+    let fill = if on { scene.palette.region } else { scene.background };
+    surface.fill_rect(corner.0, corner.1, width, tile_size, fill);
+    outline(surface, corner, (width, tile_size), scene.palette.grid);
+    surface.text(label, (corner.0 + width as i32 / 2, corner.1 + tile_size as i32 / 2), color);
+}
+
+/// The lines a cell apart in a box seven cells wide, centered on the board.
+fn draw_overlay(lines: &[String], scene: &Scene, surface: &mut dyn Surface) {
+    let tile_size = surface.tile_size();
+    let height = tile_size * lines.len() as u32;
+    let corner = (tile_size as i32, (tile_size * 9).saturating_sub(height) as i32 / 2);
+    surface.fill_rect(corner.0, corner.1, tile_size * 7, height, scene.background);
+    outline(surface, corner, (tile_size * 7, height), scene.palette.grid);
+    for (i, line) in lines.iter().enumerate() {
+        let center = ((tile_size * 9 / 2) as i32, corner.1 + (i as u32 * tile_size + tile_size / 2) as i32);
+        surface.text(line, center, scene.palette.digit);
+    }
+}
+
+/// The help's lines top to bottom in two columns, four lines to a cell, with
+/// any that don't fit left out.
+fn draw_help(scene: &Scene, surface: &mut dyn Surface) {
+    let tile_size = surface.tile_size();
+    let width = tile_size * (9 + if scene.panel.is_some() { PANEL_WIDTH } else { 0 });
+    surface.fill_rect(0, 0, width, tile_size * 9, scene.background);
+    let (line_height, per_column) = (tile_size / 4, 36);
+    for (i, line) in scene.help.iter().take(per_column * 2).enumerate() {
+        let (column, row) = ((i / per_column) as u32, (i % per_column) as u32);
+        let center = ((width / 4 + column * width / 2) as i32, (row * line_height + line_height / 2) as i32);
+        surface.small_text(line, center, scene.palette.digit);
+    }
+}
+
+/// The side of one button of the digit picker.
+fn picker_button(tile_size: u32) -> u32 {
+    tile_size * 3 / 4
+}
+
+/// The top left corner of the digit picker opened on `cell`, which is centered
+/// on the cell but kept on the board.
+pub fn picker_origin(cell: (i8, i8), tile_size: u32) -> (i32, i32) {
+    let (side, size) = ((picker_button(tile_size) * 3) as i32, (tile_size * 9) as i32);
+    let center = |i: i8| i as i32 * tile_size as i32 + tile_size as i32 / 2;
+    ((center(cell.0) - side / 2).clamp(0, size - side), (center(cell.1) - side / 2).clamp(0, size - side))
+}
+
+/// The digit whose button in the picker opened on `cell` is under `point`, if any.
+pub fn picker_digit(cell: (i8, i8), point: (i32, i32), tile_size: u32) -> Option<u8> {
+    let origin = picker_origin(cell, tile_size);
+    let button = picker_button(tile_size) as i32;
+    let (x, y) = (point.0 - origin.0, point.1 - origin.1);
+    ((0..button * 3).contains(&x) && (0..button * 3).contains(&y)).then(|| (y / button * 3 + x / button + 1) as u8)
+}
+
+/// Buttons for the nine digits laid out like a phone's keypad, with the ones
+/// noted in the cell filled in.
+fn draw_picker(cell: (i8, i8), scene: &Scene, surface: &mut dyn Surface) {
+    let tile_size = surface.tile_size();
+    let origin = picker_origin(cell, tile_size);
+    let button = picker_button(tile_size);
+    let noted = scene.notes.map_or(0, |notes| notes.get((cell.0 as usize, cell.1 as usize)));
+    for digit in 1..=9u8 {
+        let corner = (origin.0 + ((digit as u32 - 1) % 3 * button) as i32, origin.1 + ((digit as u32 - 1) / 3 * button) as i32);
+        let fill = if noted & (1 << digit) != 0 { scene.palette.region } else { scene.background };
+        surface.fill_rect(corner.0, corner.1, button, button, fill);
+        outline(surface, corner, (button, button), scene.palette.grid);
+        surface.text(&digit.to_string(), (corner.0 + button as i32 / 2, corner.1 + button as i32 / 2), scene.palette.digit);
+    }
+    outline(surface, (origin.0 - 1, origin.1 - 1), (button * 3 + 2, button * 3 + 2), scene.palette.grid);
+}
+
+fn fill_cell(surface: &mut dyn Surface, pos: (i8, i8), color: Rgb) {
+    let tile_size = surface.tile_size();
+    surface.fill_rect((pos.0 as u32 * tile_size) as _, (pos.1 as u32 * tile_size) as _, tile_size, tile_size, color);
+}
+
+fn outline(surface: &mut dyn Surface, corner: (i32, i32), size: (u32, u32), color: Rgb) {
+    surface.fill_rect(corner.0, corner.1, size.0, 1, color);
+    surface.fill_rect(corner.0, corner.1 + size.1 as i32 - 1, size.0, 1, color);
+    surface.fill_rect(corner.0, corner.1, 1, size.1, color);
+    surface.fill_rect(corner.0 + size.0 as i32 - 1, corner.1, 1, size.1, color);
+}
+
+/// The digit at `pos`, with the cell picked out if it holds the same digit as the cursor's.
+fn draw_number(board: &Board, scene: &Scene, surface: &mut dyn Surface, pos: (usize, usize)) {
+    let (cursor, palette) = (scene.cursor, scene.palette);
+    let tile_size = surface.tile_size();
+    let highlighted = scene.digit.or_else(|| cursor.and_then(|cursor| board.get((cursor.0 as usize, cursor.1 as usize)).digit()));
+    let (x, y) = pos;
+    let tile = board.get(pos);
+    let center = ((x as u32 * tile_size + tile_size / 2) as i32, (y as u32 * tile_size + tile_size / 2) as i32);
+    let under_cursor = cursor == Some((x as i8, y as i8)) || scene.selection.contains(&(x as i8, y as i8));
+    if !under_cursor && tile.digit().is_some() && tile.digit() == highlighted {
+        fill_cell(surface, (x as _, y as _), palette.same_digit);
+    }
+    match tile {
+        Tile::Hard(num) => {
+            if under_cursor {
+                fill_cell(surface, (x as _, y as _), palette.given_cursor);
+            } else if Some(num) != highlighted {
+                fill_cell(surface, (x as _, y as _), palette.given);
+            }
+            surface.text(&num.to_string(), center, palette.digit);
+            if palette.underline_givens {
+                surface.fill_rect(center.0 - (tile_size / 4) as i32, center.1 + (tile_size * 3 / 10) as i32, tile_size / 2, (tile_size / 15).max(1), palette.digit);
+            }
+        },
+        Tile::Soft(num) => {
+            let opacity = scene.fades.iter().find(|fade| fade.pos == (x as i8, y as i8) && fade.digit == num).map_or(1.0, |fade| fade.opacity);
+            surface.text(&num.to_string(), center, scene.background.blend(palette.soft_digit, opacity));
+        },
+        Tile::Empty => {
+            for fade in scene.fades.iter().filter(|fade| fade.pos == (x as i8, y as i8)) {
+                surface.text(&fade.digit.to_string(), center, scene.background.blend(palette.soft_digit, fade.opacity));
+            }
+        }
+    }
+}
+
+/// Each digit noted at `pos` in its own ninth of the cell, 1 in the top left
+/// to 9 in the bottom right.
+fn draw_notes(board: &Board, notes: &Notes, palette: &Palette, surface: &mut dyn Surface, pos: (usize, usize)) {
+    let tile_size = surface.tile_size();
+    if board.get(pos) != Tile::Empty {
+        return;
+    }
+    for digit in digits(notes.get(pos)) {
+        let sub = ((digit as u32 - 1) % 3, (digit as u32 - 1) / 3);
+        let center = (
+            (pos.0 as u32 * tile_size + sub.0 * tile_size / 3 + tile_size / 6) as i32,
+            (pos.1 as u32 * tile_size + sub.1 * tile_size / 3 + tile_size / 6) as i32
+        );
+        surface.small_text(&digit.to_string(), center, palette.digit);
+    }
+}
+
+/// The pieces of the grid inside the cell at `pos`: a thin line along the
+/// sides it shares with cells of its own box, and the borders of the boxes,
+/// as thick as the palette says, along the others. A border between two boxes
+/// is split between the cells either side of it, while the board's outer border
+/// lies wholly inside the cells along the edge.
+fn draw_grid(surface: &mut dyn Surface, palette: &Palette, pos: (usize, usize)) {
+    let tile_size = surface.tile_size();
+    let border = palette.box_border.max(1);
+    // How wide the lines are along the near and far sides of the cell in a row or column
+    let widths = |i: usize| {
+        let near = match i {
+            0 => border,
+            _ if i.is_multiple_of(3) => border - border / 2,
+            _ => 1
+        };
+        let far = match i {
+            8 => border,
+            _ if i % 3 == 2 => border / 2,
+            _ => 0
+        };
+        (near, far)
+    };
+    let corner = ((pos.0 as u32 * tile_size) as i32, (pos.1 as u32 * tile_size) as i32);
+    let ((left, right), (top, bottom)) = (widths(pos.0), widths(pos.1));
+    surface.fill_rect(corner.0, corner.1, left, tile_size, palette.grid);
+    surface.fill_rect(corner.0, corner.1, tile_size, top, palette.grid);
+    if right > 0 {
+        surface.fill_rect(corner.0 + (tile_size - right) as i32, corner.1, right, tile_size, palette.grid);
+    }
+    if bottom > 0 {
+        surface.fill_rect(corner.0, corner.1 + (tile_size - bottom) as i32, tile_size, bottom, palette.grid);
+    }
+}
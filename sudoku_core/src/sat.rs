@@ -0,0 +1,71 @@
+//! A SAT encoding of the sudoku rules, solved with varisat.
+//!
+//! This is an independent backend for cross-checking the hand-rolled solver.
+//! The formula is exposed so variant rules can add their own clauses.
+
+use varisat::{CnfFormula, ExtendFormula, Lit, Solver as SatSolver, Var};
+
+use crate::board::{get_pos, Board, Tile, Unit};
+
+/// The literal which is true when `digit` is placed at `pos`.
+pub fn cell_literal(pos: (usize, usize), digit: u8) -> Lit {
+    Lit::positive(Var::from_index((pos.1 * 9 + pos.0) * 9 + digit as usize - 1))
+}
+
+/// Encodes the sudoku rules together with every filled tile of `board`.
+pub fn sudoku_formula(board: &Board) -> CnfFormula {
+    let mut formula = CnfFormula::new();
+
+    for idx in 0..81 {
+        let pos = get_pos(idx);
+        // Every cell holds exactly one digit
+        let cell: Vec<Lit> = (1..10).map(|digit| cell_literal(pos, digit)).collect();
+        add_exactly_one(&mut formula, &cell);
+
+        if let Some(digit) = board.get(pos).digit() {
+            formula.add_clause(&[cell_literal(pos, digit)]);
+        }
+    }
+
+    // Every digit appears exactly once per row, column and box
+    for unit in Unit::all() {
+        for digit in 1..10 {
+            let spots: Vec<Lit> = unit.cells().iter().map(|&pos| cell_literal(pos, digit)).collect();
+            add_exactly_one(&mut formula, &spots);
+        }
+    }
+
+    formula
+}
+
+fn add_exactly_one(formula: &mut CnfFormula, lits: &[Lit]) {
+    formula.add_clause(lits);
+    for (i, &a) in lits.iter().enumerate() {
+        for &b in &lits[i + 1..] {
+            formula.add_clause(&[!a, !b]);
+        }
+    }
+}
+
+/// Solves `board` with the SAT backend.
+///
+/// Filled tiles are kept as they are and every empty tile in the solution is
+/// `Soft`. Returns `None` if the board has no solution.
+pub fn sat_solve(board: &Board) -> Option<Board> {
+    let mut solver = SatSolver::new();
+    solver.add_formula(&sudoku_formula(board));
+    if !solver.solve().ok()? {
+        return None;
+    }
+
+    let model = solver.model()?;
+    let mut solution = *board;
+    for lit in model.into_iter().filter(|lit| lit.is_positive()) {
+        let index = lit.var().index();
+        let pos = get_pos(index / 9);
+        if solution.get(pos) == Tile::Empty {
+            solution.set(pos, Tile::Soft((index % 9) as u8 + 1));
+        }
+    }
+    Some(solution)
+}
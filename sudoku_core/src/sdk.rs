@@ -0,0 +1,165 @@
+use std::{
+    error::Error,
+    fmt::{self, Write}
+};
+
+use crate::board::{Board, ParseBoardError, Tile};
+
+/// The metadata header of a SadMan Sudoku `.sdk` file.
+///
+/// Each field is stored on its own `#` line, tagged with a letter. Tags this
+/// type doesn't know are skipped when reading.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SdkMetadata {
+    /// `#A`
+    pub author: Option<String>,
+    /// `#D`
+    pub description: Option<String>,
+    /// `#C`
+    pub comment: Option<String>,
+    /// `#B`, the date the puzzle was published.
+    pub date: Option<String>,
+    /// `#S`
+    pub source: Option<String>,
+    /// `#L`, the difficulty level as the source rated it.
+    pub level: Option<String>,
+    /// `#U`
+    pub source_url: Option<String>
+}
+
+impl SdkMetadata {
+    fn fields(&self) -> [(char, &Option<String>); 7] {
+        [
+            ('A', &self.author),
+            ('D', &self.description),
+            ('C', &self.comment),
+            ('B', &self.date),
+            ('S', &self.source),
+            ('L', &self.level),
+            ('U', &self.source_url)
+        ]
+    }
+
+    fn field_mut(&mut self, tag: char) -> Option<&mut Option<String>> {
+        match tag {
+            'A' => Some(&mut self.author),
+            'D' => Some(&mut self.description),
+            'C' => Some(&mut self.comment),
+            'B' => Some(&mut self.date),
+            'S' => Some(&mut self.source),
+            'L' => Some(&mut self.level),
+            'U' => Some(&mut self.source_url),
+            _ => None
+        }
+    }
+}
+
+/// A puzzle read from or to be written to a `.sdk` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SdkPuzzle {
+    pub board: Board,
+    pub metadata: SdkMetadata
+}
+
+impl SdkPuzzle {
+    /// Parses a `.sdk` file: `#` metadata lines followed by nine rows of
+    /// digits and `.` for empty tiles.
+    pub fn parse(text: &str) -> Result<Self, ParseBoardError> {
+        let mut metadata = SdkMetadata::default();
+        let mut grid = String::new();
+        for line in text.lines() {
+            match line.strip_prefix('#') {
+                Some(header) => {
+                    let mut chars = header.chars();
+                    if let Some(field) = chars.next().and_then(|tag| metadata.field_mut(tag)) {
+                        *field = Some(chars.as_str().trim().to_string());
+                    }
+                },
+                None => grid.push_str(line)
+            }
+        }
+        Ok(Self { board: grid.parse()?, metadata })
+    }
+
+    /// Writes the puzzle in `.sdk` format. Only the givens are written, since
+    /// the format has no place for digits that were filled in afterwards.
+    pub fn to_sdk(&self) -> String {
+        let mut text = String::new();
+        for (tag, value) in self.metadata.fields() {
+            if let Some(value) = value {
+                let _ = writeln!(text, "#{tag}{value}");
+            }
+        }
+
+        for row in self.board.tiles() {
+            for tile in row {
+                text.push(match tile {
+                    Tile::Hard(digit) => char::from(b'0' + digit),
+                    Tile::Soft(_) | Tile::Empty => '.'
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Why a collection of puzzles couldn't be parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseCollectionError {
+    /// The line with the broken puzzle, counting from 1.
+    pub line: usize,
+    pub error: ParseBoardError
+}
+
+impl fmt::Display for ParseCollectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for ParseCollectionError {}
+
+/// Parses a SadMan Sudoku `.sdm` collection, which has one puzzle per line
+/// written as by [`Board::to_line`].
+///
+/// Anything after the puzzle on a line is ignored, so lists with extra
+/// columns such as a difficulty rating can be read as well. Blank lines and
+/// `#` comments are skipped.
+pub fn parse_sdm(text: &str) -> Result<Vec<Board>, ParseCollectionError> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let puzzle = line.split_whitespace().next().filter(|puzzle| !puzzle.starts_with('#'))?;
+            Some(puzzle.parse().map_err(|error| ParseCollectionError { line: idx + 1, error }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ESCARGOT: &str = "1....7.9..3..2...8..96..5....53..9...1..8...26....4...3......1..4......7..7...3..";
+
+    #[test]
+    fn sdk_round_trips() {
+        let grid: String = (0..9).map(|row| format!("{}\n", &ESCARGOT[row * 9..row * 9 + 9])).collect();
+        let text = format!("#AArto Inkala\n#DAI Escargot\n#Xunknown tag\n{grid}");
+        let puzzle = SdkPuzzle::parse(&text).unwrap();
+        assert_eq!(puzzle.board.to_line(), ESCARGOT);
+        assert_eq!(puzzle.metadata.author.as_deref(), Some("Arto Inkala"));
+        assert_eq!(puzzle.metadata.description.as_deref(), Some("AI Escargot"));
+        assert_eq!(SdkPuzzle::parse(&puzzle.to_sdk()), Ok(puzzle));
+    }
+
+    #[test]
+    fn sdm_skips_comments_and_extra_columns() {
+        let text = format!("# a collection\n\n{ESCARGOT} 99\n{}\n", ".".repeat(81));
+        let boards = parse_sdm(&text).unwrap();
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0].to_line(), ESCARGOT);
+        assert_eq!(boards[1], Board::new());
+        assert_eq!(parse_sdm(&format!("{ESCARGOT}\n123")), Err(ParseCollectionError { line: 2, error: ParseBoardError::WrongLength(3) }));
+    }
+}
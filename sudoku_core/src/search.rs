@@ -0,0 +1,160 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{
+    board::{get_pos, Board, Tile},
+    candidates::digits,
+    rng::Rng
+};
+
+/// Counts the solutions of `board`, stopping once `limit` have been found.
+///
+/// Unlike [`crate::Solver`] this runs a plain recursive search to completion,
+/// so it is suited for checking uniqueness rather than for display. Boards
+/// which already break the rules have no solutions.
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    if !board.is_valid() || limit == 0 {
+        return 0;
+    }
+
+    let mut board = *board;
+    let mut count = 0;
+    search(&mut board, &AtomicBool::new(false), None, &mut |_| {
+        count += 1;
+        count < limit
+    });
+    count
+}
+
+/// Finds the first solution of `board` with a plain recursive search.
+///
+/// Filled tiles are kept as they are and every empty tile in the solution is
+/// `Soft`. Returns `None` if the board has no solution.
+pub fn find_solution(board: &Board) -> Option<Board> {
+    find_solution_until(board, &AtomicBool::new(false))
+}
+
+/// Like [`find_solution`], but gives up and returns `None` once `cancel` is set.
+pub(crate) fn find_solution_until(board: &Board, cancel: &AtomicBool) -> Option<Board> {
+    if !board.is_valid() {
+        return None;
+    }
+
+    let mut board = *board;
+    let mut solution = None;
+    search(&mut board, cancel, None, &mut |found| {
+        solution = Some(*found);
+        false
+    });
+    solution
+}
+
+/// Finds a random solution of `board` by trying candidates in shuffled order.
+///
+/// On boards with several solutions, different generator states give
+/// different completions. Returns `None` if the board has no solution.
+pub fn random_solution(board: &Board, rng: &mut Rng) -> Option<Board> {
+    if !board.is_valid() {
+        return None;
+    }
+
+    let mut board = *board;
+    let mut solution = None;
+    search(&mut board, &AtomicBool::new(false), Some(rng), &mut |found| {
+        solution = Some(*found);
+        false
+    });
+    solution
+}
+
+/// The most constrained empty cell of `board` and its candidates, or `None`
+/// if the board is full.
+pub(crate) fn most_constrained(board: &Board) -> Option<((usize, usize), u16)> {
+    let mut best: Option<((usize, usize), u16)> = None;
+    for idx in 0..81 {
+        let pos = get_pos(idx);
+        if board.get(pos) != Tile::Empty {
+            continue;
+        }
+
+        let mask = board.candidates(pos);
+        if best.is_none_or(|(_, best_mask)| mask.count_ones() < best_mask.count_ones()) {
+            best = Some((pos, mask));
+            if mask.count_ones() <= 1 {
+                break;
+            }
+        }
+    }
+    best
+}
+
+/// Depth-first search over the most constrained empty cell, calling `found`
+/// with every complete board. The search stops once `found` returns false or
+/// `cancel` is set. Candidates are tried in ascending order, or shuffled if
+/// `rng` is given.
+///
+/// Returns false if the search was stopped early.
+fn search(board: &mut Board, cancel: &AtomicBool, mut rng: Option<&mut Rng>, found: &mut impl FnMut(&Board) -> bool) -> bool {
+    if cancel.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    let Some((pos, mask)) = most_constrained(board) else {
+        return found(board);
+    };
+
+    let mut order = [0; 9];
+    let mut len = 0;
+    for digit in digits(mask) {
+        order[len] = digit;
+        len += 1;
+    }
+    if let Some(rng) = rng.as_deref_mut() {
+        rng.shuffle(&mut order[..len]);
+    }
+
+    for &digit in &order[..len] {
+        board.set(pos, Tile::Soft(digit));
+        let keep_going = search(board, cancel, rng.as_deref_mut(), found);
+        board.set(pos, Tile::Empty);
+        if !keep_going {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Easter Monster, which has one solution.
+    const MONSTER: &str = "1.......2.9.4...5...6...7...5.9.3.......7.......85..4.7.....6...3...9.8...2.....1";
+
+    #[test]
+    fn counts_a_unique_solution() {
+        let board: Board = MONSTER.parse().unwrap();
+        assert_eq!(count_solutions(&board, 2), 1);
+        let solution = find_solution(&board).unwrap();
+        assert!(solution.is_full() && solution.is_valid());
+        assert_eq!(count_solutions(&solution, 2), 1);
+    }
+
+    #[test]
+    fn stops_at_the_limit() {
+        let mut board: Board = MONSTER.parse().unwrap();
+        // Without its first given the puzzle has more than one solution
+        board.set((0, 0), Tile::Empty);
+        assert_eq!(count_solutions(&board, 2), 2);
+        assert_eq!(count_solutions(&Board::new(), 5), 5);
+        assert_eq!(count_solutions(&Board::new(), 0), 0);
+    }
+
+    #[test]
+    fn invalid_boards_have_no_solutions() {
+        let mut board: Board = MONSTER.parse().unwrap();
+        board.set((1, 0), Tile::Hard(1));
+        assert!(!board.is_valid());
+        assert_eq!(count_solutions(&board, 2), 0);
+        assert!(find_solution(&board).is_none());
+    }
+}
@@ -0,0 +1,137 @@
+use std::{error::Error, fmt};
+
+use crate::board::{get_pos, Board, Tile};
+
+/// Marks a share code as a link, as in `sudoku://AbC-...`.
+pub const SHARE_PREFIX: &str = "sudoku://";
+
+/// The URL-safe base64 alphabet, so codes survive links and chat messages.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Why a share code couldn't be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseCodeError {
+    /// A character outside the URL-safe base64 alphabet.
+    InvalidCharacter(char),
+    /// The code ended before the whole board was read.
+    Truncated,
+    /// A digit outside 1 to 9, so the code wasn't made by [`to_share_code`].
+    InvalidDigit
+}
+
+impl fmt::Display for ParseCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCodeError::InvalidCharacter(c) => write!(f, "unexpected character {c:?} in share code"),
+            ParseCodeError::Truncated => write!(f, "the share code is cut short"),
+            ParseCodeError::InvalidDigit => write!(f, "the share code has a digit out of range")
+        }
+    }
+}
+
+impl Error for ParseCodeError {}
+
+/// Encodes `board` as a short code of URL-safe characters, without
+/// [`SHARE_PREFIX`].
+///
+/// The code holds which cells are filled, their digits, and which of those
+/// the solver or the player filled in, so a typical puzzle takes about 30
+/// characters and a full board with its givens marked under 90.
+pub fn to_share_code(board: &Board) -> String {
+    let filled: Vec<(u8, bool)> = board.tiles().iter().flatten().filter_map(|tile| match *tile {
+        Tile::Hard(digit) => Some((digit, false)),
+        Tile::Soft(digit) => Some((digit, true)),
+        Tile::Empty => None
+    }).collect();
+    let has_soft = filled.iter().any(|&(_, soft)| soft);
+
+    // One flag for whether soft tiles follow, one bit per cell for whether it
+    // is filled, four bits per digit, then one bit per digit if it is soft
+    let mut bits = vec![has_soft];
+    bits.extend(board.tiles().iter().flatten().map(|tile| *tile != Tile::Empty));
+    for &(digit, _) in &filled {
+        bits.extend((0..4).rev().map(|bit| (digit - 1) >> bit & 1 == 1));
+    }
+    if has_soft {
+        bits.extend(filled.iter().map(|&(_, soft)| soft));
+    }
+
+    bits.chunks(6)
+        .map(|chunk| {
+            let value = chunk.iter().enumerate().fold(0, |value, (i, &bit)| value | (bit as usize) << (5 - i));
+            ALPHABET[value] as char
+        })
+        .collect()
+}
+
+/// Reads a code made by [`to_share_code`], with or without [`SHARE_PREFIX`].
+pub fn from_share_code(code: &str) -> Result<Board, ParseCodeError> {
+    let code = code.trim();
+    let code = code.strip_prefix(SHARE_PREFIX).unwrap_or(code);
+
+    let mut bits = Vec::with_capacity(code.len() * 6);
+    for c in code.chars() {
+        let value = ALPHABET.iter().position(|&a| a as char == c).ok_or(ParseCodeError::InvalidCharacter(c))?;
+        bits.extend((0..6).rev().map(|bit| value >> bit & 1 == 1));
+    }
+    let mut bits = bits.into_iter();
+    let mut next = || bits.next().ok_or(ParseCodeError::Truncated);
+
+    let has_soft = next()?;
+    let mut filled = Vec::new();
+    for idx in 0..81 {
+        if next()? {
+            filled.push(idx);
+        }
+    }
+    let mut digits = Vec::with_capacity(filled.len());
+    for _ in &filled {
+        let mut digit = 0;
+        for _ in 0..4 {
+            digit = digit << 1 | next()? as u8;
+        }
+        if digit > 8 {
+            return Err(ParseCodeError::InvalidDigit);
+        }
+        digits.push(digit + 1);
+    }
+
+    let mut board = Board::new();
+    for (&idx, &digit) in filled.iter().zip(&digits) {
+        let tile = if has_soft && next()? { Tile::Soft(digit) } else { Tile::Hard(digit) };
+        board.set(get_pos(idx), tile);
+    }
+    Ok(board)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::find_solution;
+
+    const EVEREST: &str = "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+
+    #[test]
+    fn givens_round_trip() {
+        let board: Board = EVEREST.parse().unwrap();
+        let code = to_share_code(&board);
+        assert_eq!(from_share_code(&code), Ok(board));
+        assert_eq!(from_share_code(&format!("{SHARE_PREFIX}{code}")), Ok(board));
+        assert_eq!(from_share_code(&to_share_code(&Board::new())), Ok(Board::new()));
+    }
+
+    #[test]
+    fn entries_round_trip() {
+        let solution = find_solution(&EVEREST.parse().unwrap()).unwrap();
+        let decoded = from_share_code(&to_share_code(&solution)).unwrap();
+        assert_eq!(decoded, solution);
+        assert!(matches!(decoded.get((1, 0)), Tile::Soft(_)));
+    }
+
+    #[test]
+    fn rejects_broken_codes() {
+        let code = to_share_code(&EVEREST.parse().unwrap());
+        assert_eq!(from_share_code(&code[..code.len() / 2]), Err(ParseCodeError::Truncated));
+        assert_eq!(from_share_code("ab!"), Err(ParseCodeError::InvalidCharacter('!')));
+    }
+}
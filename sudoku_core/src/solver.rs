@@ -0,0 +1,255 @@
+use std::fmt;
+
+use crate::{
+    backtracker::Backtracker,
+    board::{cell_name, Board, Tile},
+    candidates::{digits, Candidates},
+    dlx::DlxSolver,
+    techniques::{Technique, Techniques}
+};
+
+/// A strategy for solving a board one visible step at a time.
+///
+/// Implementations record whatever they need to undo their own placements.
+/// Tiles that are already filled when solving starts are treated as fixed, so
+/// a solver should be replaced whenever the user edits the board.
+pub trait Solver {
+    /// Advances the solver by one step.
+    fn step(&mut self, board: &mut Board) -> StepResult;
+
+    /// The candidates left on `board` from this solver's point of view.
+    fn candidates(&self, board: &Board) -> Candidates {
+        Candidates::from_board(board)
+    }
+}
+
+/// The outcome of a single solver step.
+pub enum StepResult {
+    Solving(Step),
+    Finished,
+    /// Every possibility has been tried without finding a solution.
+    Unsolvable
+}
+
+/// Why the solver placed a digit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reason {
+    /// The digit was deduced by a logical technique.
+    Logic(Technique),
+    /// The solver picked one of several candidates and may have to revisit it.
+    Guess
+}
+
+/// A single change the solver made, for frontends to display.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// A digit was written to the board.
+    Place { pos: (usize, usize), digit: u8, reason: Reason },
+    /// Candidates were removed from cells.
+    Eliminate { eliminations: Vec<((usize, usize), u16)>, technique: Technique },
+    /// A placement was taken back while backtracking.
+    Unplace { pos: (usize, usize), digit: u8 },
+    /// Earlier eliminations were taken back while backtracking.
+    Restore { eliminations: Vec<((usize, usize), u16)> }
+}
+
+impl Step {
+    /// Makes the change to the board again, as when replaying a solve.
+    ///
+    /// Eliminations only live in the solver, so they leave the board alone.
+    pub fn apply(&self, board: &mut Board) {
+        match *self {
+            Step::Place { pos, digit, .. } => board.set(pos, Tile::Soft(digit)),
+            Step::Unplace { pos, .. } => board.set(pos, Tile::Empty),
+            Step::Eliminate { .. } | Step::Restore { .. } => ()
+        }
+    }
+
+    /// Takes the change back, leaving the board as it was before the step.
+    pub fn revert(&self, board: &mut Board) {
+        match *self {
+            Step::Place { pos, .. } => board.set(pos, Tile::Empty),
+            Step::Unplace { pos, digit } => board.set(pos, Tile::Soft(digit)),
+            Step::Eliminate { .. } | Step::Restore { .. } => ()
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Place { pos, digit, reason: Reason::Logic(technique) } => {
+                write!(f, "{} = {digit} ({technique})", cell_name(*pos))
+            },
+            Step::Place { pos, digit, reason: Reason::Guess } => write!(f, "{} = {digit} (guess)", cell_name(*pos)),
+            Step::Eliminate { eliminations, technique } => {
+                write_eliminations(f, eliminations)?;
+                write!(f, " ({technique})")
+            },
+            Step::Unplace { pos, digit } => write!(f, "backtrack: cleared {} (was {digit})", cell_name(*pos)),
+            Step::Restore { eliminations } => {
+                write!(f, "backtrack: restored ")?;
+                write_eliminations(f, eliminations)
+            }
+        }
+    }
+}
+
+fn write_eliminations(f: &mut fmt::Formatter<'_>, eliminations: &[((usize, usize), u16)]) -> fmt::Result {
+    for (i, &(pos, mask)) in eliminations.iter().enumerate() {
+        if i > 0 {
+            write!(f, "; ")?;
+        }
+        let removed: Vec<String> = digits(mask).map(|digit| digit.to_string()).collect();
+        write!(f, "{} -{}", cell_name(pos), removed.join(","))?;
+    }
+    Ok(())
+}
+
+/// Every step a solver took, in order, for explaining a solve afterwards.
+///
+/// The log can also be rewound, taking steps back off the board, and then
+/// replayed up to where the solver left off. Until it has caught up again,
+/// the board no longer matches the solver and new steps must not be pushed.
+#[derive(Clone, Debug, Default)]
+pub struct SolveLog {
+    steps: Vec<Step>,
+    /// The number of steps currently applied to the board.
+    position: usize
+}
+
+impl SolveLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, step: Step) {
+        debug_assert!(!self.is_rewound(), "pushed a step to a rewound log");
+        self.steps.push(step);
+        self.position = self.steps.len();
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// The number of steps currently applied to the board.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Whether some logged steps have been taken back off the board.
+    pub fn is_rewound(&self) -> bool {
+        self.position < self.steps.len()
+    }
+
+    /// Takes the latest applied step back off `board`, returning it, or
+    /// `None` if the log is already at the start.
+    pub fn rewind(&mut self, board: &mut Board) -> Option<&Step> {
+        self.position = self.position.checked_sub(1)?;
+        let step = &self.steps[self.position];
+        step.revert(board);
+        Some(step)
+    }
+
+    /// Applies the next rewound step to `board` again, returning it, or
+    /// `None` if the log has caught up with the solver.
+    pub fn replay(&mut self, board: &mut Board) -> Option<&Step> {
+        let step = self.steps.get(self.position)?;
+        step.apply(board);
+        self.position += 1;
+        Some(step)
+    }
+
+    pub fn clear(&mut self) {
+        self.steps.clear();
+        self.position = 0;
+    }
+}
+
+impl fmt::Display for SolveLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(f, "{:>5}. {step}", i + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// How the solver picks the cell to guess in when no technique applies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CellOrder {
+    /// The first empty cell from the top left, row by row.
+    #[default]
+    Raster,
+    /// The empty cell with the fewest candidates, which keeps the search tree small.
+    MostConstrained
+}
+
+impl fmt::Display for CellOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellOrder::Raster => write!(f, "row by row"),
+            CellOrder::MostConstrained => write!(f, "fewest candidates first")
+        }
+    }
+}
+
+/// The solving algorithms to choose from at runtime.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// Backtracking without any logical techniques.
+    BruteForce,
+    /// Backtracking after applying every enabled technique.
+    #[default]
+    LogicFirst,
+    /// Knuth's Algorithm X with dancing links.
+    Dlx
+}
+
+impl Strategy {
+    /// The strategy after this one, wrapping around, for cycling in a UI.
+    pub fn next(self) -> Self {
+        match self {
+            Strategy::BruteForce => Strategy::LogicFirst,
+            Strategy::LogicFirst => Strategy::Dlx,
+            Strategy::Dlx => Strategy::BruteForce
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Strategy::BruteForce => write!(f, "brute force"),
+            Strategy::LogicFirst => write!(f, "logic first"),
+            Strategy::Dlx => write!(f, "dancing links")
+        }
+    }
+}
+
+/// Settings for a [`Solver`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SolverOptions {
+    pub strategy: Strategy,
+    /// The logical techniques to apply before guessing.
+    pub techniques: Techniques,
+    pub order: CellOrder,
+    /// If set, guesses try candidates in a random order drawn from this seed
+    /// instead of ascending, giving a random solution on ambiguous boards.
+    pub seed: Option<u64>
+}
+
+impl SolverOptions {
+    /// Creates a fresh solver for the chosen strategy.
+    pub fn build(self) -> Box<dyn Solver> {
+        match self.strategy {
+            Strategy::BruteForce => Box::new(Backtracker::with_options(SolverOptions {
+                techniques: Techniques::none(),
+                ..self
+            })),
+            Strategy::LogicFirst => Box::new(Backtracker::with_options(self)),
+            Strategy::Dlx => Box::new(DlxSolver::new())
+        }
+    }
+}
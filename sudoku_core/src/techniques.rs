@@ -0,0 +1,336 @@
+use std::fmt;
+
+use crate::{
+    board::{box_index, get_pos, Board, Tile, Unit},
+    candidates::{digits, Candidates}
+};
+
+/// A logical technique the solver can use to deduce a digit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Technique {
+    /// The cell has only one candidate left.
+    NakedSingle,
+    /// The digit has only one possible cell left in the unit.
+    HiddenSingle(Unit),
+    /// Within a box, the digit is confined to one row or column, so it can be
+    /// removed from the rest of that line.
+    Pointing { from: Unit, line: Unit },
+    /// Within a row or column, the digit is confined to one box, so it can be
+    /// removed from the rest of that box.
+    BoxLineReduction { from: Unit, within: Unit },
+    /// `size` cells of the unit share exactly `size` candidates, which can be
+    /// removed from the other cells of the unit.
+    NakedSubset { unit: Unit, size: usize },
+    /// `size` candidates of the unit are confined to exactly `size` cells, so
+    /// every other candidate can be removed from those cells.
+    HiddenSubset { unit: Unit, size: usize }
+}
+
+/// Which logical techniques the solver is allowed to use.
+///
+/// Disabling every technique leaves plain backtracking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Techniques {
+    pub naked_single: bool,
+    pub hidden_single: bool,
+    pub locked_candidates: bool,
+    pub naked_pair: bool,
+    pub naked_triple: bool,
+    pub naked_quad: bool,
+    pub hidden_pair: bool,
+    pub hidden_triple: bool,
+    pub hidden_quad: bool
+}
+
+impl Techniques {
+    pub const fn all() -> Self {
+        Self {
+            naked_single: true,
+            hidden_single: true,
+            locked_candidates: true,
+            naked_pair: true,
+            naked_triple: true,
+            naked_quad: true,
+            hidden_pair: true,
+            hidden_triple: true,
+            hidden_quad: true
+        }
+    }
+
+    pub const fn none() -> Self {
+        Self {
+            naked_single: false,
+            hidden_single: false,
+            locked_candidates: false,
+            naked_pair: false,
+            naked_triple: false,
+            naked_quad: false,
+            hidden_pair: false,
+            hidden_triple: false,
+            hidden_quad: false
+        }
+    }
+
+    /// Runs the enabled techniques from simplest to most involved and returns
+    /// the first deduction found.
+    pub fn deduce(&self, board: &Board, candidates: &Candidates) -> Option<Deduction> {
+        let naked = [self.naked_pair, self.naked_triple, self.naked_quad];
+        let hidden = [self.hidden_pair, self.hidden_triple, self.hidden_quad];
+
+        // Contradictions are always checked so backtracking starts as early as possible.
+        let contradiction = candidates_exhausted(board, candidates).then_some(Deduction::Contradiction);
+        contradiction
+            .or_else(|| self.naked_single.then(|| naked_single(board, candidates)).flatten())
+            .or_else(|| self.hidden_single.then(|| hidden_single(board, candidates)).flatten())
+            .or_else(|| self.locked_candidates.then(|| locked_candidates(candidates)).flatten())
+            .or_else(|| (2..5).find_map(|size| {
+                let naked = naked[size - 2].then(|| naked_subset(candidates, size)).flatten();
+                naked.or_else(|| hidden[size - 2].then(|| hidden_subset(candidates, size)).flatten())
+            }))
+    }
+}
+
+impl Default for Techniques {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Technique::NakedSingle => write!(f, "naked single"),
+            Technique::HiddenSingle(unit) => write!(f, "hidden single in {unit}"),
+            Technique::Pointing { from, line } => write!(f, "pointing candidates in {from} along {line}"),
+            Technique::BoxLineReduction { from, within } => write!(f, "box/line reduction in {from} within {within}"),
+            Technique::NakedSubset { unit, size } => write!(f, "naked {} in {unit}", subset_name(*size)),
+            Technique::HiddenSubset { unit, size } => write!(f, "hidden {} in {unit}", subset_name(*size))
+        }
+    }
+}
+
+fn subset_name(size: usize) -> &'static str {
+    match size {
+        2 => "pair",
+        3 => "triple",
+        4 => "quad",
+        _ => "subset"
+    }
+}
+
+/// Something a technique found out about the board.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Deduction {
+    /// `digit` must go at `pos`.
+    Place { pos: (usize, usize), digit: u8, technique: Technique },
+    /// The candidates in each mask can be removed from the paired cell.
+    Eliminate { eliminations: Vec<((usize, usize), u16)>, technique: Technique },
+    /// The board cannot be completed from its current state.
+    Contradiction
+}
+
+/// Finds a cell with exactly one candidate.
+///
+/// Reports a contradiction if any empty cell has no candidates at all.
+pub fn naked_single(board: &Board, candidates: &Candidates) -> Option<Deduction> {
+    let mut found = None;
+    for idx in 0..81 {
+        let pos = get_pos(idx);
+        if board.get(pos) != Tile::Empty {
+            continue;
+        }
+
+        let mask = candidates.get(pos);
+        match mask.count_ones() {
+            0 => return Some(Deduction::Contradiction),
+            1 => {
+                found.get_or_insert(Deduction::Place {
+                    pos,
+                    digit: mask.trailing_zeros() as u8,
+                    technique: Technique::NakedSingle
+                });
+            },
+            _ => ()
+        }
+    }
+    found
+}
+
+/// Finds a digit which can only go in one cell of some row, column or box.
+///
+/// Reports a contradiction if a digit missing from a unit has nowhere to go.
+pub fn hidden_single(board: &Board, candidates: &Candidates) -> Option<Deduction> {
+    let mut found = None;
+    for unit in Unit::all() {
+        let cells = unit.cells();
+        for digit in digits(!board.placed(unit)) {
+            let mut spots = cells.iter().filter(|&&pos| candidates.contains(pos, digit));
+            match (spots.next(), spots.next()) {
+                (None, _) => return Some(Deduction::Contradiction),
+                (Some(&pos), None) => {
+                    found.get_or_insert(Deduction::Place {
+                        pos,
+                        digit,
+                        technique: Technique::HiddenSingle(unit)
+                    });
+                },
+                _ => ()
+            }
+        }
+    }
+    found
+}
+
+/// Finds pointing candidates and box/line reductions.
+///
+/// Only reports a deduction if it removes at least one candidate.
+pub fn locked_candidates(candidates: &Candidates) -> Option<Deduction> {
+    for unit in Unit::all() {
+        let cells = unit.cells();
+        for digit in 1..10 {
+            let spots: Vec<(usize, usize)> = cells.iter().copied().filter(|&pos| candidates.contains(pos, digit)).collect();
+            let Some(&first) = spots.first() else { continue };
+
+            // The other unit every remaining spot shares with `first`, if any.
+            let target = match unit {
+                Unit::Box(_) if spots.iter().all(|pos| pos.1 == first.1) => Unit::Row(first.1),
+                Unit::Box(_) if spots.iter().all(|pos| pos.0 == first.0) => Unit::Column(first.0),
+                Unit::Row(_) | Unit::Column(_) if spots.iter().all(|&pos| box_index(pos) == box_index(first)) => {
+                    Unit::Box(box_index(first))
+                },
+                _ => continue
+            };
+
+            let eliminations: Vec<((usize, usize), u16)> = target
+                .cells()
+                .into_iter()
+                .filter(|pos| !cells.contains(pos) && candidates.contains(*pos, digit))
+                .map(|pos| (pos, 1 << digit))
+                .collect();
+            if eliminations.is_empty() {
+                continue;
+            }
+
+            let technique = match unit {
+                Unit::Box(_) => Technique::Pointing { from: unit, line: target },
+                _ => Technique::BoxLineReduction { from: unit, within: target }
+            };
+            return Some(Deduction::Eliminate { eliminations, technique });
+        }
+    }
+    None
+}
+
+/// Returns true if an empty cell has no candidates left.
+fn candidates_exhausted(board: &Board, candidates: &Candidates) -> bool {
+    (0..81).map(get_pos).any(|pos| board.get(pos) == Tile::Empty && candidates.get(pos) == 0)
+}
+
+/// Finds `size` cells in a unit whose candidates together make up exactly
+/// `size` digits.
+pub fn naked_subset(candidates: &Candidates, size: usize) -> Option<Deduction> {
+    for unit in Unit::all() {
+        let cells = unit.cells();
+        for subset in subsets(size) {
+            let members = || (0..9).filter(move |i| subset & (1 << i) != 0).map(|i| cells[i]);
+            if members().any(|pos| candidates.get(pos) == 0) {
+                continue;
+            }
+
+            let shared = members().fold(0, |mask, pos| mask | candidates.get(pos));
+            if shared.count_ones() as usize != size {
+                continue;
+            }
+
+            let eliminations: Vec<((usize, usize), u16)> = (0..9)
+                .filter(|i| subset & (1 << i) == 0)
+                .map(|i| cells[i])
+                .filter(|&pos| candidates.get(pos) & shared != 0)
+                .map(|pos| (pos, candidates.get(pos) & shared))
+                .collect();
+            if !eliminations.is_empty() {
+                return Some(Deduction::Eliminate { eliminations, technique: Technique::NakedSubset { unit, size } });
+            }
+        }
+    }
+    None
+}
+
+/// Finds `size` digits in a unit which can only go in the same `size` cells.
+pub fn hidden_subset(candidates: &Candidates, size: usize) -> Option<Deduction> {
+    for unit in Unit::all() {
+        let cells = unit.cells();
+        // For every digit, the cells of the unit it can still go in.
+        let spots: [u16; 10] = std::array::from_fn(|digit| {
+            (0..9).filter(|&i| digit > 0 && candidates.contains(cells[i], digit as u8)).fold(0, |mask, i| mask | 1 << i)
+        });
+
+        for subset in subsets(size) {
+            let digit_mask = subset << 1;
+            let members = || digits(digit_mask).map(|digit| spots[digit as usize]);
+            if members().any(|mask| mask == 0) {
+                continue;
+            }
+
+            let shared = members().fold(0, |mask, spot| mask | spot);
+            if shared.count_ones() as usize != size {
+                continue;
+            }
+
+            let eliminations: Vec<((usize, usize), u16)> = (0..9)
+                .filter(|i| shared & (1 << i) != 0)
+                .map(|i| cells[i])
+                .filter(|&pos| candidates.get(pos) & !digit_mask != 0)
+                .map(|pos| (pos, candidates.get(pos) & !digit_mask))
+                .collect();
+            if !eliminations.is_empty() {
+                return Some(Deduction::Eliminate { eliminations, technique: Technique::HiddenSubset { unit, size } });
+            }
+        }
+    }
+    None
+}
+
+/// Every 9-bit mask with exactly `size` bits set.
+fn subsets(size: usize) -> impl Iterator<Item = u16> {
+    (0u16..1 << 9).filter(move |mask| mask.count_ones() as usize == size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::find_solution;
+
+    const EASY: &str = "8..9.3....39..8.5...........7..6.....5.4..91..1..2.54..4......5.....1...2.5..4.3.";
+
+    /// The solution of an easy puzzle with only the cell at `pos` taken out,
+    /// with the digit that was there.
+    fn one_missing(pos: (usize, usize)) -> (Board, u8) {
+        let mut board = find_solution(&EASY.parse().unwrap()).unwrap();
+        let digit = board.get(pos).digit().unwrap();
+        board.set(pos, Tile::Empty);
+        (board, digit)
+    }
+
+    #[test]
+    fn singles_fill_the_last_cell() {
+        let (board, missing) = one_missing((4, 4));
+        let candidates = Candidates::from_board(&board);
+        assert!(matches!(
+            naked_single(&board, &candidates),
+            Some(Deduction::Place { pos: (4, 4), digit, technique: Technique::NakedSingle }) if digit == missing
+        ));
+        assert!(matches!(
+            hidden_single(&board, &candidates),
+            Some(Deduction::Place { pos: (4, 4), digit, technique: Technique::HiddenSingle(_) }) if digit == missing
+        ));
+    }
+
+    #[test]
+    fn nothing_to_find_on_a_full_board() {
+        let board = find_solution(&EASY.parse().unwrap()).unwrap();
+        let candidates = Candidates::from_board(&board);
+        assert!(naked_single(&board, &candidates).is_none());
+        assert!(hidden_single(&board, &candidates).is_none());
+    }
+}
@@ -0,0 +1,359 @@
+//! The solver in a browser page, drawn on a `<canvas>` through the same
+//! [`sudoku_core::render`] code as the window. Built with
+//! `wasm-pack build web --target web` and served along with `web/index.html`.
+//!
+//! Browsers have no clock or threads the core can use, so the page passes in
+//! the time between frames and puzzles can't be generated here.
+
+use sudoku_core::{
+    count_solutions,
+    render::{self, Palette, Rgb, Scene, Surface},
+    Board, CellOrder, Progress, SolveLog, Solver, SolverOptions, Step, StepResult, Tile
+};
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+use fixtures::test_board;
+
+#[path = "../../src/fixtures.rs"]
+mod fixtures;
+
+/// The visual solving speeds to choose from with `+` and `-`, in steps per second.
+const SPEEDS: &[u32] = &[1, 2, 5, 10, 20, 60, 120, 300, 600, 1200, 3000, 6000, 12000];
+const DEFAULT_SPEED: usize = 5;
+/// How many steps to take per frame when solving without visuals.
+const UNTHROTTLED_STEPS: u32 = 4096;
+
+/// The window's default colors.
+const PALETTE: Palette = Palette {
+    grid: Rgb(0, 0, 0),
+    digit: Rgb(0, 0, 0),
+    soft_digit: Rgb(30, 70, 170),
+    given: Rgb(255, 255, 0),
+    given_cursor: Rgb(200, 200, 0),
+    cursor: Rgb(200, 200, 200),
+    region: Rgb(220, 240, 220),
+    changed: Rgb(200, 220, 255),
+    same_digit: Rgb(255, 220, 180),
+    peers: Rgb(238, 238, 245),
+    wrong: Rgb(220, 0, 0),
+    progress: Rgb(0, 160, 0),
+    underline_givens: false,
+    box_border: 3
+};
+const BACKGROUND: Rgb = Rgb(255, 255, 255);
+const NO_SOLUTION: Rgb = Rgb(255, 220, 220);
+const SEVERAL_SOLUTIONS: Rgb = Rgb(255, 250, 200);
+const ERROR: Rgb = Rgb(200, 0, 0);
+
+/// A 2D canvas as a [`Surface`].
+struct CanvasSurface<'a> {
+    context: &'a CanvasRenderingContext2d,
+    tile_size: u32
+}
+
+fn css(color: Rgb) -> String {
+    format!("rgb({}, {}, {})", color.0, color.1, color.2)
+}
+
+impl Surface for CanvasSurface<'_> {
+    fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32, color: Rgb) {
+        self.context.set_fill_style_str(&css(color));
+        self.context.fill_rect(x as f64, y as f64, width as f64, height as f64);
+    }
+
+    fn text(&mut self, text: &str, center: (i32, i32), color: Rgb) {
+        self.text_sized(text, center, color, self.tile_size * 2 / 3);
+    }
+
+    fn small_text(&mut self, text: &str, center: (i32, i32), color: Rgb) {
+        self.text_sized(text, center, color, self.tile_size * 2 / 9);
+    }
+}
+
+impl CanvasSurface<'_> {
+    fn text_sized(&mut self, text: &str, center: (i32, i32), color: Rgb, size: u32) {
+        self.context.set_fill_style_str(&css(color));
+        self.context.set_font(&format!("{size}px monospace"));
+        self.context.set_text_align("center");
+        self.context.set_text_baseline("middle");
+        let _ = self.context.fill_text(text, center.0 as f64, center.1 as f64);
+    }
+}
+
+/// A board with its solver, driven by the page's key presses and animation frames.
+#[wasm_bindgen]
+pub struct Game {
+    context: CanvasRenderingContext2d,
+    tile_size: u32,
+    board: Board,
+    cursor: (i8, i8),
+    solving: bool,
+    step_once: bool,
+    finished: bool,
+    unsolvable: bool,
+    visual_solving: bool,
+    speed: usize,
+    step_debt: f64,
+    solver_options: SolverOptions,
+    solver: Box<dyn Solver>,
+    last_step: Option<Step>,
+    log: SolveLog,
+    progress: Progress,
+    solutions: usize,
+    /// The answer to the last key that has something to say.
+    message: String
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Draws on `canvas`, which should be square, with the cells sized to fill it.
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas: HtmlCanvasElement) -> Result<Game, JsValue> {
+        let context = canvas
+            .get_context("2d")?
+            .ok_or("the canvas has no 2D context")?
+            .dyn_into::<CanvasRenderingContext2d>()?;
+        let board = Board::new();
+        let solver_options = SolverOptions::default();
+        let game = Game {
+            context,
+            tile_size: canvas.width().min(canvas.height()) / 9,
+            board,
+            cursor: (0, 0),
+            solving: false,
+            step_once: false,
+            finished: false,
+            unsolvable: false,
+            visual_solving: true,
+            speed: DEFAULT_SPEED,
+            step_debt: 0.0,
+            solver: solver_options.build(),
+            solver_options,
+            last_step: None,
+            log: SolveLog::new(),
+            progress: Progress::new(&board),
+            solutions: count_solutions(&board, 2),
+            message: String::new()
+        };
+        game.draw();
+        Ok(game)
+    }
+
+    /// Puts a puzzle written as one line of 81 characters, or as a grid, on the board.
+    pub fn load(&mut self, puzzle: &str) -> Result<(), JsValue> {
+        self.board = puzzle.parse().map_err(|err| JsValue::from_str(&format!("{err}")))?;
+        self.reset();
+        self.draw();
+        Ok(())
+    }
+
+    /// The board as one line of 81 characters, with `.` for empty cells.
+    pub fn export(&self) -> String {
+        self.board.to_line()
+    }
+
+    /// Handles a key by its `KeyboardEvent.key` name, with the same keys as
+    /// the window. Returns true if the key did something.
+    pub fn key(&mut self, key: &str) -> bool {
+        let pos = (self.cursor.0 as usize, self.cursor.1 as usize);
+        let mut edited = false;
+        match key {
+            "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9" => {
+                self.board.set(pos, Tile::Hard(key.as_bytes()[0] - b'0'));
+                edited = true;
+            },
+            "Backspace" | "Delete" => {
+                self.board.set(pos, Tile::Empty);
+                edited = true;
+            },
+            "ArrowRight" => self.move_cursor(1, 0),
+            "ArrowLeft" => self.move_cursor(-1, 0),
+            "ArrowUp" => self.move_cursor(0, -1),
+            "ArrowDown" => self.move_cursor(0, 1),
+            " " => {
+                if self.board.is_valid() {
+                    self.solving = !self.solving;
+                }
+            },
+            "n" => {
+                if self.board.is_valid() && !self.solving {
+                    self.step_once = true;
+                }
+            },
+            "b" => {
+                self.solving = false;
+                if let Some(step) = self.log.rewind(&mut self.board) {
+                    self.progress.unrecord(step);
+                    self.last_step = Some(step.clone());
+                    self.finished = false;
+                    self.unsolvable = false;
+                }
+            },
+            "+" | "=" | "]" => {
+                self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+                self.message = format!("Speed: {} steps per second", SPEEDS[self.speed]);
+            },
+            "-" | "[" => {
+                self.speed = self.speed.saturating_sub(1);
+                self.message = format!("Speed: {} steps per second", SPEEDS[self.speed]);
+            },
+            "v" => {
+                self.visual_solving = !self.visual_solving;
+                self.message = format!("Visual solving: {}", if self.visual_solving { "on" } else { "off" });
+            },
+            "s" => {
+                // Takes effect the next time the solver is reset
+                self.solver_options.strategy = self.solver_options.strategy.next();
+                self.message = format!("Strategy: {}", self.solver_options.strategy);
+            },
+            "o" => {
+                self.solver_options.order = match self.solver_options.order {
+                    CellOrder::Raster => CellOrder::MostConstrained,
+                    CellOrder::MostConstrained => CellOrder::Raster
+                };
+                self.message = format!("Order: {:?}", self.solver_options.order);
+            },
+            "t" => {
+                self.board = test_board();
+                edited = true;
+            },
+            _ => return false
+        }
+        if edited {
+            self.reset();
+        }
+        self.draw();
+        true
+    }
+
+    /// Takes the solver steps due in `elapsed_ms` since the last frame and
+    /// redraws the board. Call it from `requestAnimationFrame`.
+    pub fn tick(&mut self, elapsed_ms: f64) {
+        // Visual solving is paced by the clock rather than by the frame rate,
+        // carrying over fractions of a step until they add up to a whole one
+        let steps = if self.step_once {
+            1
+        } else if !self.solving {
+            0
+        } else if self.visual_solving {
+            self.step_debt += SPEEDS[self.speed] as f64 * elapsed_ms / 1000.0;
+            let steps = self.step_debt as u32;
+            self.step_debt -= steps as f64;
+            steps
+        } else {
+            UNTHROTTLED_STEPS
+        };
+        self.step_once = false;
+
+        for _ in 0..steps {
+            if self.finished {
+                break;
+            }
+            match advance(self.solver.as_mut(), &mut self.board, &mut self.progress, &mut self.log) {
+                StepResult::Solving(step) => self.last_step = Some(step),
+                result => {
+                    self.solving = false;
+                    self.finished = true;
+                    self.unsolvable = matches!(result, StepResult::Unsolvable);
+                    self.last_step = None;
+                }
+            }
+        }
+        if steps > 0 {
+            self.draw();
+        }
+    }
+
+    /// The solve progress and the answer to the last key, for showing under the canvas.
+    pub fn status(&self) -> String {
+        let paused = !self.solving && !self.finished && self.log.position() > 0;
+        let mut status = Vec::new();
+        if self.solving || paused {
+            status.push(format!(
+                "{} nodes, {} backtracks{}",
+                self.progress.nodes,
+                self.progress.backtracks,
+                if paused { " (paused)" } else { "" }
+            ));
+        }
+        if !self.message.is_empty() {
+            status.push(self.message.clone());
+        }
+        status.join(", ")
+    }
+}
+
+impl Game {
+    fn move_cursor(&mut self, x: i8, y: i8) {
+        self.cursor = ((self.cursor.0 + x).clamp(0, 8), (self.cursor.1 + y).clamp(0, 8));
+    }
+
+    /// Starts the solver over on the edited board.
+    fn reset(&mut self) {
+        self.solving = false;
+        self.solver = self.solver_options.build();
+        self.last_step = None;
+        self.log.clear();
+        self.progress = Progress::new(&self.board);
+        self.finished = false;
+        self.unsolvable = false;
+        self.solutions = count_solutions(&self.board, 2);
+    }
+
+    fn draw(&self) {
+        let paused = !self.solving && !self.finished && self.log.position() > 0;
+        let background = match self.solutions {
+            _ if self.solving || paused => BACKGROUND,
+            0 => NO_SOLUTION,
+            1 => BACKGROUND,
+            _ => SEVERAL_SOLUTIONS
+        };
+        let scene = Scene {
+            palette: &PALETTE,
+            background,
+            cursor: Some(self.cursor),
+            region: None,
+            progress: (self.solving || paused).then_some(&self.progress),
+            banner: self.unsolvable.then_some(("No solution", ERROR)),
+            last_step: self.last_step.as_ref(),
+            players: &[],
+            notes: None,
+            coloring: None,
+            picker: None,
+            wrong: &[],
+            selection: &[],
+            digit: None,
+            panel: None,
+            menu: None,
+            status: &[],
+            fades: &[],
+            overlay: &[],
+            toast: None,
+            help: &[]
+        };
+        render::draw(&self.board, &scene, &mut CanvasSurface { context: &self.context, tile_size: self.tile_size });
+    }
+}
+
+/// Takes one solver step and records it in `progress` and `log`.
+///
+/// If the log has been rewound, the next logged step is replayed instead, so
+/// the solver only continues once the board is back where it left off.
+fn advance(solver: &mut dyn Solver, board: &mut Board, progress: &mut Progress, log: &mut SolveLog) -> StepResult {
+    if let Some(step) = log.replay(board) {
+        progress.record(step);
+        return StepResult::Solving(step.clone());
+    }
+
+    let result = solver.step(board);
+    if let StepResult::Solving(step) = &result {
+        progress.record(step);
+        log.push(step.clone());
+    }
+    result
+}